@@ -1,29 +1,9 @@
-use speechmatics::realtime::*;
+use speechmatics::realtime::{transcript::TranscriptBuilder, *};
 use std::{
     path::PathBuf,
     sync::{Arc, Mutex},
 };
-use tokio::{self, fs::File, try_join};
-
-struct MockStore {
-    transcript: String,
-}
-
-impl MockStore {
-    pub fn new() -> Self {
-        Self {
-            transcript: "".to_owned(),
-        }
-    }
-
-    pub fn append(&mut self, transcript: String) {
-        self.transcript = format!("{} {}", self.transcript, transcript);
-    }
-
-    pub fn print(&self) {
-        print!("{}", self.transcript)
-    }
-}
+use tokio::{self, fs::File, join};
 
 #[tokio::main]
 async fn main() {
@@ -42,31 +22,23 @@ async fn main() {
     let audio_config = models::AudioFormat::new(models::audio_format::Type::File);
     config.audio_format = Some(audio_config);
 
-    let mock_store = Arc::new(Mutex::new(MockStore::new()));
-    let mock_store_clone = mock_store.clone();
+    let transcript = Arc::new(Mutex::new(TranscriptBuilder::new()));
+    let transcript_clone = transcript.clone();
 
     let message_task = tokio::spawn(async move {
         while let Some(message) = receive_channel.recv().await {
-            match message {
-                ReadMessage::AddTranscript(mess) => {
-                    mock_store_clone
-                        .lock()
-                        .unwrap()
-                        .append(mess.metadata.transcript);
-                }
-                ReadMessage::EndOfTranscript(_) => return,
-                _ => {}
+            if let ReadMessage::EndOfTranscript(_) = message {
+                return;
             }
+            transcript_clone.lock().unwrap().handle_message(&message);
         }
     });
 
     let run_task = { rt_session.run(config, file) };
 
-    try_join!(
-        async move { message_task.await.map_err(anyhow::Error::from) },
-        run_task
-    )
-    .unwrap();
+    let (message_res, run_res) = join!(message_task, run_task);
+    message_res.unwrap();
+    run_res.unwrap();
 
-    mock_store.lock().unwrap().print();
+    print!("{}", transcript.lock().unwrap().final_text());
 }
@@ -1,6 +1,6 @@
 use speechmatics::batch::{
     models::{self, JobConfig, TranscriptionConfig},
-    BatchClient,
+    BatchClient, PollPolicy,
 };
 use std::path::PathBuf;
 use tokio;
@@ -30,22 +30,10 @@ async fn main() {
         .unwrap();
 
     // wait for the job to return a completed status, or to enter an error status in which case panic
-    let mut success = false;
-    let mut retries = 0;
-    while !success {
-        let get_job_res = batch_client.get_job(&job_res.id).await.unwrap();
-        if get_job_res.job.status == models::job_details::Status::Done {
-            success = true
-        } else if get_job_res.job.status != models::job_details::Status::Running {
-            panic!("Job failed");
-        } else {
-            if retries > 6 {
-                panic!("Job took too long to complete");
-            }
-            retries += 1;
-            std::thread::sleep(std::time::Duration::from_millis(3000));
-        }
-    }
+    batch_client
+        .wait_for_job(&job_res.id, PollPolicy::default())
+        .await
+        .unwrap();
 
     // get the json transcript of the job
     let get_result_res = batch_client.get_json_result(&job_res.id).await.unwrap();
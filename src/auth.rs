@@ -0,0 +1,372 @@
+//! Providers of the bearer tokens that the batch and realtime clients authenticate with.
+//!
+//! A client asks its provider for a token before each request or connection, so long-lived API keys can be kept on a
+//! server, and clients such as browsers or edge devices can be given short-lived temporary keys instead.
+
+use crate::error::{ApiError, Error, Result};
+use futures::{future::BoxFuture, lock::Mutex, FutureExt};
+use log::error;
+use reqwest::Client;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+use url::Url;
+
+/// The Speechmatics endpoint which issues temporary keys.
+pub const DEFAULT_TOKEN_URL: &str = "https://mp.speechmatics.com/v1/api_keys";
+
+/// The shortest time to live that the Speechmatics endpoint accepts for a temporary key.
+pub const MIN_TEMPORARY_KEY_TTL: Duration = Duration::from_secs(60);
+
+/// AuthProvider supplies the bearer token sent with each batch request and each realtime connection.
+///
+/// It is implemented for StaticKey and TemporaryKeyProvider, and can be implemented to fetch tokens from elsewhere.
+/// A provider can be shared between clients by wrapping it in an Arc.
+pub trait AuthProvider: Send + Sync {
+    /// Returns the token to authenticate the next request or connection with.
+    ///
+    /// # Errors
+    ///
+    /// Implementations should return Error::TokenRefresh if a token could not be issued.
+    fn token(&self) -> BoxFuture<'_, Result<String>>;
+}
+
+impl<T: AuthProvider + ?Sized> AuthProvider for std::sync::Arc<T> {
+    fn token(&self) -> BoxFuture<'_, Result<String>> {
+        (**self).token()
+    }
+}
+
+/// A long-lived API key, which is sent as it is with every request.
+#[derive(Clone)]
+pub struct StaticKey {
+    key: String,
+}
+
+impl StaticKey {
+    /// Creates a provider which always returns the given key.
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into() }
+    }
+}
+
+impl fmt::Debug for StaticKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticKey").finish_non_exhaustive()
+    }
+}
+
+impl AuthProvider for StaticKey {
+    fn token(&self) -> BoxFuture<'_, Result<String>> {
+        futures::future::ready(Ok(self.key.clone())).boxed()
+    }
+}
+
+/// The API that a temporary key can be used with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum TemporaryKeyType {
+    /// A key for the realtime API.
+    Realtime,
+    /// A key for the batch API.
+    Batch,
+}
+
+impl fmt::Display for TemporaryKeyType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Realtime => write!(f, "rt"),
+            Self::Batch => write!(f, "batch"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TemporaryKeyResponse {
+    key_value: String,
+}
+
+struct CachedKey {
+    key: String,
+    refresh_at: Instant,
+}
+
+/// TemporaryKeyProvider fetches short-lived keys from a token endpoint, and caches each key until it is close to expiring.
+///
+/// Keys are requested with a POST to the token endpoint, with the key type in the type query parameter and the
+/// time to live (in seconds) in a json body of the form `{"ttl": 60}`. The endpoint must respond with a json body
+/// containing the key in its key_value property. This is the format of the Speechmatics endpoint, so a backend
+/// which holds the long-lived key can pass requests straight through to it.
+///
+/// A cached key is replaced once less than a fifth of its time to live remains, so that a connection is never started
+/// with a key that is about to expire. If fetching a key fails for any reason, e.g. the endpoint can't be reached, it
+/// rejects the request, or its response can't be parsed, Error::TokenRefresh is returned with the cause inside it.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn run() {
+/// use speechmatics::{
+///     auth::{TemporaryKeyProvider, TemporaryKeyType},
+///     realtime::{RealtimeSession, SessionConfig},
+/// };
+/// use std::time::Duration;
+///
+/// let auth = TemporaryKeyProvider::new("API_KEY", TemporaryKeyType::Realtime, Duration::from_secs(60)).unwrap();
+/// let (mut rt_session, _) = RealtimeSession::with_auth(auth, None).unwrap();
+/// # }
+/// ```
+///
+pub struct TemporaryKeyProvider {
+    client: Client,
+    token_url: Url,
+    api_key: Option<String>,
+    key_type: TemporaryKeyType,
+    ttl: Duration,
+    /// How long a key is used for before it is replaced.
+    refresh_after: Duration,
+    cached: Mutex<Option<CachedKey>>,
+}
+
+impl TemporaryKeyProvider {
+    /// Creates a provider which fetches keys from the Speechmatics token endpoint, authenticated with a long-lived API key.
+    /// Each key is valid for ttl, which the API requires to be at least 60 seconds.
+    ///
+    /// # Errors
+    ///
+    /// This function will return Error::InvalidInput if ttl is shorter than MIN_TEMPORARY_KEY_TTL,
+    /// or Error::Transport if the HTTP client can't be created.
+    pub fn new(api_key: &str, key_type: TemporaryKeyType, ttl: Duration) -> Result<Self> {
+        Self::with_token_url(Url::parse(DEFAULT_TOKEN_URL)?, Some(api_key), key_type, ttl)
+    }
+
+    /// Creates a provider which fetches keys from a custom token endpoint, e.g. a backend which issues keys to logged in users.
+    /// If api_key is set, it is sent to the endpoint as a bearer token.
+    ///
+    /// # Errors
+    ///
+    /// This function will return Error::InvalidInput if ttl is shorter than MIN_TEMPORARY_KEY_TTL,
+    /// or Error::Transport if the HTTP client can't be created.
+    pub fn with_token_url(
+        token_url: Url,
+        api_key: Option<&str>,
+        key_type: TemporaryKeyType,
+        ttl: Duration,
+    ) -> Result<Self> {
+        Self::with_client(
+            Client::builder().build()?,
+            token_url,
+            api_key,
+            key_type,
+            ttl,
+        )
+    }
+
+    /// Creates a provider which fetches keys with the given HTTP client, e.g. one which goes through the same proxy
+    /// or trusts the same root certificates as the BatchClientBuilder or RealtimeSessionBuilder it is used with.
+    /// The client must come from the same version of reqwest as this crate uses.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use speechmatics::auth::{TemporaryKeyProvider, TemporaryKeyType, DEFAULT_TOKEN_URL};
+    /// use std::time::Duration;
+    ///
+    /// let client = reqwest::Client::builder()
+    ///     .proxy(reqwest::Proxy::all("http://proxy.internal:3128").unwrap())
+    ///     .timeout(Duration::from_secs(10))
+    ///     .build()
+    ///     .unwrap();
+    /// let auth = TemporaryKeyProvider::with_client(
+    ///     client,
+    ///     DEFAULT_TOKEN_URL.parse().unwrap(),
+    ///     Some("API_KEY"),
+    ///     TemporaryKeyType::Realtime,
+    ///     Duration::from_secs(60),
+    /// )
+    /// .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return Error::InvalidInput if ttl is shorter than MIN_TEMPORARY_KEY_TTL.
+    pub fn with_client(
+        client: Client,
+        token_url: Url,
+        api_key: Option<&str>,
+        key_type: TemporaryKeyType,
+        ttl: Duration,
+    ) -> Result<Self> {
+        if ttl < MIN_TEMPORARY_KEY_TTL {
+            return Err(Error::InvalidInput(format!(
+                "temporary keys must live for at least {:?}, not {:?}",
+                MIN_TEMPORARY_KEY_TTL, ttl
+            )));
+        }
+        Ok(Self {
+            client,
+            token_url,
+            api_key: api_key.map(str::to_owned),
+            key_type,
+            ttl,
+            refresh_after: ttl.mul_f64(0.8),
+            cached: Mutex::new(None),
+        })
+    }
+
+    async fn fetch(&self) -> Result<String> {
+        let mut request = self
+            .client
+            .post(self.token_url.clone())
+            .query(&[("type", self.key_type.to_string())])
+            .json(&serde_json::json!({ "ttl": self.ttl.as_secs() }));
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let res = request.send().await?;
+        let status = res.status();
+        let body = res.bytes().await?;
+        if !status.is_success() {
+            error!("failed to fetch a temporary key, {}", status);
+            return Err(Error::Auth(ApiError::Http {
+                status: status.as_u16(),
+                body: String::from_utf8_lossy(&body).into_owned(),
+            }));
+        }
+        Ok(serde_json::from_slice::<TemporaryKeyResponse>(&body)?.key_value)
+    }
+}
+
+impl fmt::Debug for TemporaryKeyProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TemporaryKeyProvider")
+            .field("token_url", &self.token_url)
+            .field("key_type", &self.key_type)
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuthProvider for TemporaryKeyProvider {
+    fn token(&self) -> BoxFuture<'_, Result<String>> {
+        async move {
+            // the lock is held whilst fetching, so that concurrent callers share a single new key
+            let mut cached = self.cached.lock().await;
+            if let Some(cached) = cached.as_ref().filter(|c| c.refresh_at > Instant::now()) {
+                return Ok(cached.key.clone());
+            }
+            let fetched_at = Instant::now();
+            let key = self
+                .fetch()
+                .await
+                .map_err(|err| Error::TokenRefresh(Box::new(err)))?;
+            *cached = Some(CachedKey {
+                key: key.clone(),
+                refresh_at: fetched_at + self.refresh_after,
+            });
+            Ok(key)
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{header, method, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_temporary_key_provider() {
+        let mock_server = MockServer::start().await;
+        let token_url = Url::parse(&format!("{}/v1/api_keys", mock_server.uri())).unwrap();
+        Mock::given(method("POST"))
+            .and(query_param("type", "rt"))
+            .and(header("Authorization", "Bearer API_KEY"))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .set_body_json(serde_json::json!({"apikey_id": "ID", "key_value": "TEMP_KEY"})),
+            )
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(header("Authorization", "Bearer WRONG_KEY"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+            .mount(&mock_server)
+            .await;
+        let provider = |api_key, ttl| {
+            TemporaryKeyProvider::with_token_url(
+                token_url.clone(),
+                Some(api_key),
+                TemporaryKeyType::Realtime,
+                ttl,
+            )
+            .unwrap()
+        };
+
+        // the second token comes from the cache
+        let auth = provider("API_KEY", Duration::from_secs(60));
+        assert_eq!(auth.token().await.unwrap(), "TEMP_KEY");
+        assert_eq!(auth.token().await.unwrap(), "TEMP_KEY");
+
+        // keys which have expired are refreshed
+        let mut auth = provider("API_KEY", Duration::from_secs(60));
+        auth.refresh_after = Duration::ZERO;
+        assert_eq!(auth.token().await.unwrap(), "TEMP_KEY");
+        assert_eq!(auth.token().await.unwrap(), "TEMP_KEY");
+
+        // keys are fetched with the client the provider was created with
+        Mock::given(method("POST"))
+            .and(header("X-Request-Source", "test"))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .set_body_json(serde_json::json!({"key_value": "CLIENT_KEY"})),
+            )
+            .with_priority(1)
+            .mount(&mock_server)
+            .await;
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("X-Request-Source", "test".parse().unwrap());
+        let client = Client::builder().default_headers(headers).build().unwrap();
+        let auth = TemporaryKeyProvider::with_client(
+            client,
+            token_url.clone(),
+            Some("API_KEY"),
+            TemporaryKeyType::Realtime,
+            Duration::from_secs(60),
+        )
+        .unwrap();
+        assert_eq!(auth.token().await.unwrap(), "CLIENT_KEY");
+
+        let auth = provider("WRONG_KEY", Duration::from_secs(60));
+        match auth.token().await {
+            Err(Error::TokenRefresh(err)) => match *err {
+                Error::Auth(ApiError::Http { status, body }) => {
+                    assert_eq!(status, 401);
+                    assert_eq!(body, "Unauthorized");
+                }
+                err => panic!("unexpected error {:?}", err),
+            },
+            res => panic!("unexpected result {:?}", res),
+        }
+
+        // an unreachable endpoint is a failed refresh rather than a transport error
+        let unreachable = Url::parse("http://127.0.0.1:1/v1/api_keys").unwrap();
+        let auth = TemporaryKeyProvider::with_token_url(
+            unreachable,
+            Some("API_KEY"),
+            TemporaryKeyType::Realtime,
+            Duration::from_secs(60),
+        )
+        .unwrap();
+        let err = auth.token().await.unwrap_err();
+        assert!(matches!(&err, Error::TokenRefresh(err) if matches!(**err, Error::Transport(_))));
+
+        let res =
+            TemporaryKeyProvider::new("API_KEY", TemporaryKeyType::Batch, Duration::from_secs(59));
+        assert!(matches!(res, Err(Error::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,179 @@
+//! Types and parsers for the outputs of alignment jobs, which add timing information to a text file for a given audio file.
+
+use crate::error::{Error, Result};
+
+/// The format of the timing information returned for an alignment job.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum AlignmentTags {
+    /// Tags are inserted at the start and end of each word, e.g. `<time=0.41>hello<time=0.76>`. This is the default.
+    #[default]
+    WordStartAndEnd,
+    /// A tag is inserted at the start of each line, e.g. `[00:00:00.4]    hello world`.
+    OnePerLine,
+}
+
+impl std::fmt::Display for AlignmentTags {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::WordStartAndEnd => write!(f, "word_start_and_end"),
+            Self::OnePerLine => write!(f, "one_per_line"),
+        }
+    }
+}
+
+/// A word from the aligned text file, with the times (in seconds) that it starts and ends in the audio.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AlignedWord {
+    /// The word as it appears in the text file.
+    pub content: String,
+    /// The time the word starts at in seconds.
+    pub start_time: f32,
+    /// The time the word ends at in seconds.
+    pub end_time: f32,
+}
+
+/// A line from the aligned text file, with the time (in seconds) that it starts in the audio.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AlignedLine {
+    /// The text of the line as it appears in the text file.
+    pub content: String,
+    /// The time the line starts at in seconds.
+    pub start_time: f32,
+}
+
+const TIME_TAG_START: &str = "<time=";
+
+/// Parses alignment output with word_start_and_end tags into a list of words.
+///
+/// Tags come in pairs around each word, and any text between two pairs (e.g. whitespace or punctuation) is skipped.
+///
+/// # Errors
+///
+/// This function will return Error::Decode if a tag is not terminated, its time is not a number, or a word is missing its end tag.
+pub fn parse_word_start_and_end(text: &str) -> Result<Vec<AlignedWord>> {
+    let mut tags = vec![];
+    let mut rest = text;
+    let mut offset = 0;
+    while let Some(start) = rest.find(TIME_TAG_START) {
+        let value_start = start + TIME_TAG_START.len();
+        let value_len = rest[value_start..]
+            .find('>')
+            .ok_or_else(|| decode_error(format!("unterminated time tag in {}", rest)))?;
+        let value = &rest[value_start..value_start + value_len];
+        let time = value
+            .parse::<f32>()
+            .map_err(|_| decode_error(format!("invalid time {} in alignment", value)))?;
+        let tag_end = value_start + value_len + 1;
+        tags.push((time, offset + start, offset + tag_end));
+        rest = &rest[tag_end..];
+        offset += tag_end;
+    }
+
+    if tags.len() % 2 != 0 {
+        return Err(decode_error(
+            "alignment has a word without an end tag".to_owned(),
+        ));
+    }
+
+    Ok(tags
+        .chunks(2)
+        .map(|pair| {
+            let (start_time, _, content_start) = pair[0];
+            let (end_time, content_end, _) = pair[1];
+            AlignedWord {
+                content: text[content_start..content_end].to_owned(),
+                start_time,
+                end_time,
+            }
+        })
+        .collect())
+}
+
+/// Parses alignment output with one_per_line tags into a list of lines.
+/// Lines without a tag, e.g. blank lines, are skipped.
+///
+/// # Errors
+///
+/// This function will return Error::Decode if a tag is not terminated or does not contain a valid HH:MM:SS.s timestamp.
+pub fn parse_one_per_line(text: &str) -> Result<Vec<AlignedLine>> {
+    let mut lines = vec![];
+    for line in text.lines() {
+        let line = line.trim_start();
+        let Some(tagged) = line.strip_prefix('[') else {
+            continue;
+        };
+        let tag_len = tagged
+            .find(']')
+            .ok_or_else(|| decode_error(format!("unterminated time tag in {}", line)))?;
+        let start_time = parse_timestamp(&tagged[..tag_len])?;
+        lines.push(AlignedLine {
+            content: tagged[tag_len + 1..].trim().to_owned(),
+            start_time,
+        });
+    }
+    Ok(lines)
+}
+
+/// Parses a HH:MM:SS.s timestamp into seconds
+fn parse_timestamp(timestamp: &str) -> Result<f32> {
+    let invalid = || decode_error(format!("invalid timestamp {} in alignment", timestamp));
+    let mut seconds = 0.0;
+    for part in timestamp.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f32>().map_err(|_| invalid())?;
+    }
+    Ok(seconds)
+}
+
+fn decode_error(reason: String) -> Error {
+    Error::Decode(reason.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_word_start_and_end() {
+        let text = "<time=0.41>Hello<time=0.76>, <time=0.89>world<time=1.18>.\n";
+        let words = parse_word_start_and_end(text).unwrap();
+        assert_eq!(
+            words,
+            vec![
+                AlignedWord {
+                    content: "Hello".to_owned(),
+                    start_time: 0.41,
+                    end_time: 0.76
+                },
+                AlignedWord {
+                    content: "world".to_owned(),
+                    start_time: 0.89,
+                    end_time: 1.18
+                },
+            ]
+        );
+
+        assert!(parse_word_start_and_end("<time=0.41>hello").is_err());
+        assert!(parse_word_start_and_end("<time=abc>hello<time=1>").is_err());
+    }
+
+    #[test]
+    fn test_parse_one_per_line() {
+        let text = "[00:00:00.4]    hello world\n\n[00:01:02.5]    second line\n";
+        let lines = parse_one_per_line(text).unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                AlignedLine {
+                    content: "hello world".to_owned(),
+                    start_time: 0.4
+                },
+                AlignedLine {
+                    content: "second line".to_owned(),
+                    start_time: 62.5
+                },
+            ]
+        );
+
+        assert!(parse_one_per_line("[00:00:xx]    hello").is_err());
+    }
+}
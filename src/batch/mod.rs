@@ -1,17 +1,39 @@
 //! The main entry point for the batch jobs API. Provides a struct which wraps a client and comes with associated API methods.
 
-use anyhow::Result;
+use crate::auth::{AuthProvider, StaticKey};
+use crate::error::{Error, Result};
+use alignment::{AlignedLine, AlignedWord, AlignmentTags};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use futures::{
+    future::{self, Either},
+    pin_mut,
+    stream::{self, TryStreamExt},
+    Future, Stream,
+};
+use futures_timer::Delay;
+use log::warn;
 use models::*;
 use reqwest::{
-    header::{HeaderMap, HeaderValue, AUTHORIZATION},
-    multipart::{Form, Part},
-    Client,
+    header::{HeaderMap, HeaderName, HeaderValue},
+    multipart::Form,
+    Client, Method, RequestBuilder, Response,
+};
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
 };
-use std::fs;
+use transcript::{SrtTranscript, Transcript, TranscriptFormat};
+use upload::JobFile;
 use url::Url;
 
+pub mod alignment;
 #[allow(missing_docs)]
 pub mod models;
+pub mod subtitles;
+pub mod transcript;
+pub mod upload;
+pub mod usage;
 
 /// The default URL for the batch runtime.
 ///
@@ -20,6 +42,229 @@ pub mod models;
 /// A full list of URLs can be found in our [docs](https://docs.speechmatics.com/introduction/authentication#supported-endpoints).
 pub const DEFAULT_BATCH_URL: &str = "https://asr.api.speechmatics.com/v2/";
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+const DEFAULT_PAGE_SIZE: i32 = 100;
+
+/// PollPolicy controls how often wait_for_job checks the status of a job, and for how long.
+///
+/// The job is checked straight away, then again after initial_interval. The interval is then multiplied
+/// by backoff_factor after each check, up to max_interval. It implements default, which starts at 1 second,
+/// backs off to a maximum of 30 seconds and never times out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PollPolicy {
+    /// The time to wait after the first check of the job status.
+    pub initial_interval: Duration,
+    /// The maximum time to wait between two checks of the job status.
+    pub max_interval: Duration,
+    /// The factor the interval is multiplied by after each check. Values below 1 are treated as 1.
+    pub backoff_factor: f64,
+    /// The total time to wait for the job to finish. If None, polling continues until the job finishes.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for PollPolicy {
+    fn default() -> Self {
+        Self {
+            initial_interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(30),
+            backoff_factor: 2.0,
+            timeout: None,
+        }
+    }
+}
+
+/// JobsFilter controls which jobs are returned by jobs_stream, and how many are fetched per request.
+///
+/// It implements default, which fetches 100 jobs per request, starting from the most recent job and excluding deleted jobs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JobsFilter {
+    /// The number of jobs to fetch per request. If None, 100 jobs are fetched per request.
+    pub page_size: Option<i32>,
+    /// Whether to include jobs with a status of deleted.
+    pub include_deleted: Option<bool>,
+    /// Only return jobs created before this UTC date time, e.g. the created_at of a job. If None, starts from the most recent job.
+    pub created_before: Option<String>,
+}
+
+/// Where jobs_stream has got to. created_before is a millisecond after the last job of the previous page,
+/// and seen holds the ids of the jobs from that page which can be returned again.
+struct JobsCursor {
+    created_before: Option<String>,
+    seen: HashSet<String>,
+}
+
+impl JobsCursor {
+    /// Returns the cursor for the page after the given one, where last_created_at is the created_at of its last job.
+    fn next(&self, jobs: &[JobDetails], last_created_at: &str) -> JobsCursor {
+        let exclusive = || JobsCursor {
+            created_before: Some(last_created_at.to_owned()),
+            seen: HashSet::new(),
+        };
+        let Ok(last_time) = DateTime::parse_from_rfc3339(last_created_at) else {
+            warn!(
+                "Could not parse created_at {}, jobs created at the same time may be skipped",
+                last_created_at
+            );
+            return exclusive();
+        };
+        let next_time = last_time + chrono::Duration::milliseconds(1);
+        let created_before = next_time
+            .with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Millis, true);
+        if self.created_before.as_deref() == Some(created_before.as_str()) {
+            // the whole page was created within the same millisecond, so the only way on is to skip past it
+            warn!(
+                "More than a page of jobs were created at {}, some of them may be skipped",
+                last_created_at
+            );
+            return exclusive();
+        }
+        let seen = jobs
+            .iter()
+            .filter(|job| {
+                DateTime::parse_from_rfc3339(&job.created_at).map_or(true, |time| time < next_time)
+            })
+            .map(|job| job.id.clone())
+            .collect();
+        JobsCursor {
+            created_before: Some(created_before),
+            seen,
+        }
+    }
+}
+
+/// BatchClientBuilder configures how a BatchClient connects to the API. It is created with BatchClient::builder,
+/// and an API key or auth provider must be set before calling build. Everything else is optional.
+///
+/// The settings match those of RealtimeSessionBuilder, so both clients can be configured in the same way.
+#[derive(Default)]
+pub struct BatchClientBuilder {
+    auth: Option<Arc<dyn AuthProvider>>,
+    batch_url: Option<Url>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<Url>,
+    root_certificates: Vec<Vec<u8>>,
+    headers: Vec<(String, String)>,
+}
+
+impl BatchClientBuilder {
+    /// Authenticates with a long-lived API key.
+    pub fn api_key(self, api_key: &str) -> Self {
+        self.auth(StaticKey::new(api_key))
+    }
+
+    /// Authenticates with a token from the auth provider before each request, e.g. a temporary key.
+    /// A TemporaryKeyProvider fetches keys with its own HTTP client, so to fetch them through the same proxy
+    /// or with the same root certificates, create it with TemporaryKeyProvider::with_client.
+    pub fn auth(mut self, auth: impl AuthProvider + 'static) -> Self {
+        self.auth = Some(Arc::new(auth));
+        self
+    }
+
+    /// Sets the URL of the batch API. Defaults to DEFAULT_BATCH_URL.
+    pub fn url(mut self, batch_url: Url) -> Self {
+        self.batch_url = Some(batch_url);
+        self
+    }
+
+    /// Sets the time allowed for connecting to the API. By default, connecting is not timed out.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the time allowed for each request, from connecting until the response body has been read.
+    /// This includes uploading the audio, so it should allow for the largest file that will be submitted.
+    /// By default, requests are not timed out.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends all requests through an HTTP or HTTPS proxy. Credentials can be set in the URL.
+    pub fn proxy(mut self, proxy: Url) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Trusts the PEM encoded certificate as a root, in addition to the usual roots,
+    /// e.g. for an on-prem deployment which uses a private certificate authority.
+    pub fn add_root_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    /// Adds a header which is sent with every request.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Builds the client.
+    ///
+    /// # Errors
+    ///
+    /// This function will return Error::InvalidInput if no API key or auth provider was set,
+    /// or if a header, proxy URL or certificate is invalid.
+    pub fn build(self) -> Result<BatchClient> {
+        let auth = self.auth.ok_or_else(|| {
+            Error::InvalidInput("an API key or auth provider is required".to_owned())
+        })?;
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|err| Error::InvalidInput(err.to_string()))?;
+            headers.append(name, HeaderValue::from_str(value)?);
+        }
+        let mut builder = Client::builder().default_headers(headers);
+        #[cfg(feature = "rustls")]
+        {
+            builder = builder.use_rustls_tls();
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = self.proxy {
+            if !matches!(proxy.scheme(), "http" | "https") || proxy.host_str().is_none() {
+                return Err(Error::InvalidInput(format!(
+                    "unsupported proxy {}, only HTTP and HTTPS proxies are supported",
+                    proxy
+                )));
+            }
+            let proxy =
+                reqwest::Proxy::all(proxy).map_err(|err| Error::InvalidInput(err.to_string()))?;
+            builder = builder.proxy(proxy);
+        }
+        #[cfg(any(feature = "native-tls", feature = "rustls"))]
+        for pem in &self.root_certificates {
+            let certificate = reqwest::Certificate::from_pem(pem)
+                .map_err(|err| Error::InvalidInput(err.to_string()))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        #[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+        if !self.root_certificates.is_empty() {
+            return Err(Error::InvalidInput(
+                "root certificates require the native-tls or rustls feature".to_owned(),
+            ));
+        }
+
+        let default_query = vec![("sm-sdk".to_owned(), format!("rs-{}", VERSION))];
+
+        Ok(BatchClient {
+            batch_url: match self.batch_url {
+                Some(batch_url) => batch_url,
+                None => Url::parse(DEFAULT_BATCH_URL)?,
+            },
+            client: builder.build()?,
+            auth,
+            default_query,
+        })
+    }
+}
 
 /// BatchClient - batch client is the main wrapper for making batch requests.
 /// It holds the url in question along with the client object.
@@ -27,33 +272,70 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub struct BatchClient {
     batch_url: Url,
     client: Client,
+    auth: Arc<dyn AuthProvider>,
     default_query: Vec<(String, String)>,
 }
 
 impl BatchClient {
     /// Instantiates a new batch client. Parses the provided URL or use the defaults
     pub fn new(api_key: &str, batch_url: Option<url::Url>) -> Result<Self> {
-        let mut headers = HeaderMap::new();
-
-        let auth_header = format!("Bearer {}", api_key);
-        headers.append(AUTHORIZATION, HeaderValue::from_str(&auth_header)?);
+        Self::with_auth(StaticKey::new(api_key), batch_url)
+    }
 
-        let client = Client::builder().default_headers(headers).build()?;
-        let mut set_url = Url::parse(DEFAULT_BATCH_URL)?;
+    /// Instantiates a new batch client which asks the auth provider for a bearer token before each request,
+    /// e.g. to use temporary keys instead of a long-lived API key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use speechmatics::{
+    ///     auth::{TemporaryKeyProvider, TemporaryKeyType},
+    ///     batch::BatchClient,
+    /// };
+    /// use std::time::Duration;
+    ///
+    /// let auth = TemporaryKeyProvider::new("API_KEY", TemporaryKeyType::Batch, Duration::from_secs(3600)).unwrap();
+    /// let batch_client = BatchClient::with_auth(auth, None).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will error if the HTTP client can't be created.
+    pub fn with_auth(
+        auth: impl AuthProvider + 'static,
+        batch_url: Option<url::Url>,
+    ) -> Result<Self> {
+        let mut builder = Self::builder().auth(auth);
         if let Some(batch_url_set) = batch_url {
-            set_url = batch_url_set
+            builder = builder.url(batch_url_set)
         }
+        builder.build()
+    }
 
-        let default_query = vec![("sm-sdk".to_owned(), format!("rs-{}", VERSION))];
-
-        Ok(Self {
-            batch_url: set_url,
-            client,
-            default_query,
-        })
+    /// Returns a builder for configuring how the client connects to the API, e.g. to set timeouts, a proxy or extra headers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use speechmatics::batch::BatchClient;
+    /// use std::time::Duration;
+    ///
+    /// let batch_client = BatchClient::builder()
+    ///     .api_key("API_KEY")
+    ///     .connect_timeout(Duration::from_secs(5))
+    ///     .timeout(Duration::from_secs(300))
+    ///     .header("X-Request-Source", "example")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder() -> BatchClientBuilder {
+        BatchClientBuilder::default()
     }
 
-    /// Submits a job to the batch jobs API based on a path to a file.
+    /// Submits a job to the batch jobs API with the given audio file.
+    ///
+    /// The file can be a path or any other [`JobFile`], e.g. one created from an AsyncRead or a Stream of bytes.
+    /// It is streamed into the request rather than read into memory, so large recordings can be uploaded without buffering them.
     ///
     /// # Example
     ///
@@ -84,35 +366,137 @@ impl BatchClient {
     /// # Errors
     ///
     /// The following error states are possible:
-    ///     - If the file can't be read (e.g. it doesn't exist)
-    ///     - If there is an issue converting the file path to a file name string
+    ///     - If the file can't be opened (e.g. it doesn't exist)
+    ///     - If the file fails to read part way through the upload, which is returned as a transport error
     ///     - If there is an error in the API, which could be any standard HTTP error code
     ///     - If the response cannot be parsed from bytes into the correct struct
     ///
     pub async fn submit_job(
         &self,
         config: JobConfig,
-        file_path: std::path::PathBuf,
+        file: impl Into<JobFile>,
     ) -> Result<CreateJobResponse> {
-        let url = self.batch_url.join("jobs")?;
-        let file = fs::read(&file_path)?;
-        let config_text = serde_json::to_string(&config)?;
+        let data_file = file.into().into_part().await.map_err(Error::AudioRead)?;
+        let form = Form::new().part("data_file", data_file);
+        self.create_job(config, form).await
+    }
 
-        let mut file_name: String = "".to_owned();
-        if let Some(fil_name) = file_path.file_name() {
-            if let Some(string_file) = fil_name.to_str() {
-                file_name = string_file.to_owned();
-            }
+    /// Submits an alignment job to the batch jobs API, with an audio file and the text file that should be aligned to it.
+    /// Both files can be any [`JobFile`].
+    ///
+    /// The config must have a type of alignment and an alignment_config.
+    /// The results can be retrieved with get_alignment, get_aligned_words or get_aligned_lines once the job is done.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() {
+    /// use std::path::PathBuf;
+    /// use speechmatics::batch::{
+    ///     BatchClient,
+    ///     models::{AlignmentConfig, JobConfig, JobType}
+    /// };
+    ///
+    /// let batch_client = BatchClient::new("API_KEY", None).unwrap();
+    ///
+    /// let mut config = JobConfig::new(JobType::Alignment);
+    /// config.alignment_config = Some(Box::new(AlignmentConfig::new("en".to_owned())));
+    ///
+    /// let job_res = batch_client
+    ///     .submit_alignment_job(config, PathBuf::from("example.wav"), PathBuf::from("example.txt"))
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The same errors as submit_job are possible. Additionally, Error::InvalidInput is returned
+    /// if the config is not for an alignment job or if the text file can't be read.
+    ///
+    pub async fn submit_alignment_job(
+        &self,
+        config: JobConfig,
+        file: impl Into<JobFile>,
+        text_file: impl Into<JobFile>,
+    ) -> Result<CreateJobResponse> {
+        if config.type_value != JobType::Alignment || config.alignment_config.is_none() {
+            return Err(Error::InvalidInput(
+                "alignment jobs need a type of alignment and an alignment_config".to_owned(),
+            ));
         }
+        let data_file = file.into().into_part().await.map_err(Error::AudioRead)?;
+        let text_file = text_file
+            .into()
+            .into_part()
+            .await
+            .map_err(|err| Error::InvalidInput(format!("failed to read text file: {}", err)))?;
+        let form = Form::new()
+            .part("data_file", data_file)
+            .part("text_file", text_file);
+        self.create_job(config, form).await
+    }
 
-        let some_file = Part::stream(file).file_name(file_name);
+    /// Submits a job whose audio (and, for alignment jobs, text) is fetched by the server from a URL,
+    /// rather than uploaded. Only the config is sent, so the audio never passes through the client.
+    ///
+    /// The config must have fetch_data set, along with fetch_text for alignment jobs.
+    /// Any auth_headers are passed on by the server when fetching, e.g. to access private object storage.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() {
+    /// use speechmatics::batch::{
+    ///     BatchClient,
+    ///     models::{DataFetchConfig, JobConfig, TranscriptionConfig}
+    /// };
+    ///
+    /// let batch_client = BatchClient::new("API_KEY", None).unwrap();
+    ///
+    /// let mut config = JobConfig::default();
+    /// config.transcription_config = Some(Box::new(TranscriptionConfig::new("en".to_owned())));
+    /// let mut fetch_data = DataFetchConfig::new("https://example.com/audio.wav".to_owned());
+    /// fetch_data.auth_headers = Some(vec!["Authorization: Bearer TOKEN".to_owned()]);
+    /// config.fetch_data = Some(Box::new(fetch_data));
+    ///
+    /// let job_res = batch_client.submit_fetch_job(config).await.unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Error::InvalidInput is returned if fetch_data (or fetch_text for alignment jobs) is missing, if a URL is not a valid
+    /// http(s) URL, or if an auth header is not of the form `Name: value`.
+    /// Otherwise the same errors as submit_job are possible, apart from those caused by reading a file.
+    ///
+    pub async fn submit_fetch_job(&self, config: JobConfig) -> Result<CreateJobResponse> {
+        validate_fetch_config("fetch_data", config.fetch_data.as_deref())?;
+        if config.type_value == JobType::Alignment {
+            validate_fetch_config("fetch_text", config.fetch_text.as_deref())?;
+        }
+        self.create_job(config, Form::new()).await
+    }
 
-        let form = Form::new()
-            .part("data_file", some_file)
-            .text("config", config_text);
+    /// Starts a request, authenticated with a bearer token from the auth provider.
+    async fn request(&self, method: Method, url: Url) -> Result<RequestBuilder> {
+        let token = self.auth.token().await?;
+        Ok(self.client.request(method, url).bearer_auth(token))
+    }
+
+    async fn create_job(&self, config: JobConfig, form: Form) -> Result<CreateJobResponse> {
+        let url = self.batch_url.join("jobs")?;
+        let config_text = serde_json::to_string(&config)?;
 
-        let res = self.client.post(url).multipart(form).send().await;
-        let result = res?.error_for_status()?.bytes().await?;
+        let form = form.text("config", config_text);
+
+        let res = self
+            .request(Method::POST, url)
+            .await?
+            .multipart(form)
+            .send()
+            .await;
+        let result = check_response(res?).await?.bytes().await?;
 
         let serde_res = serde_json::from_slice::<CreateJobResponse>(&result)?;
         Ok(serde_res)
@@ -137,13 +521,91 @@ impl BatchClient {
     pub async fn get_job(&self, job_id: &str) -> Result<RetrieveJobResponse> {
         let url = self.batch_url.join("jobs/")?.join(job_id)?;
 
-        let res = self.client.get(url).send().await;
-        let result = res?.error_for_status()?.bytes().await?;
+        let res = self.request(Method::GET, url).await?.send().await;
+        let result = check_response(res?).await?.bytes().await?;
 
         let serde_res = serde_json::from_slice::<RetrieveJobResponse>(&result)?;
         Ok(serde_res)
     }
 
+    /// Waits for a job to finish by polling get_job according to the provided PollPolicy, and returns the final job details.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() {
+    /// use std::time::Duration;
+    /// use speechmatics::batch::{BatchClient, PollPolicy};
+    ///
+    /// let batch_client = BatchClient::new("API_KEY", None).unwrap();
+    /// let policy = PollPolicy {
+    ///     timeout: Some(Duration::from_secs(600)),
+    ///     ..Default::default()
+    /// };
+    /// let job = batch_client.wait_for_job("JOB_ID", policy).await.unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function can error with the usual HTTP status code errors from get_job.
+    /// If the job is rejected, deleted or expires, Error::JobFailed is returned with the job details, which include any errors reported for the job.
+    /// If the job is still running when the timeout of the policy is reached, Error::Timeout is returned.
+    ///
+    pub async fn wait_for_job(&self, job_id: &str, policy: PollPolicy) -> Result<JobDetails> {
+        self.wait_for_job_with_cancel(job_id, policy, future::pending())
+            .await
+    }
+
+    /// The same as wait_for_job, but stops polling as soon as the cancel future completes.
+    /// This could be a oneshot receiver or a cancellation token from your runtime of choice.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as wait_for_job are possible. If polling is cancelled, Error::Cancelled is returned.
+    ///
+    pub async fn wait_for_job_with_cancel<F: Future<Output = ()>>(
+        &self,
+        job_id: &str,
+        policy: PollPolicy,
+        cancel: F,
+    ) -> Result<JobDetails> {
+        let poll = self.poll_job(job_id, policy);
+        pin_mut!(poll, cancel);
+        match future::select(poll, cancel).await {
+            Either::Left((res, _)) => res,
+            Either::Right(_) => Err(Error::Cancelled),
+        }
+    }
+
+    async fn poll_job(&self, job_id: &str, policy: PollPolicy) -> Result<JobDetails> {
+        let started = Instant::now();
+        let mut interval = policy.initial_interval;
+        loop {
+            let job = self.get_job(job_id).await?.job;
+            match job.status {
+                job_details::Status::Done => return Ok(*job),
+                job_details::Status::Running => {}
+                _ => return Err(Error::JobFailed(job)),
+            }
+
+            let mut wait = interval;
+            if let Some(timeout) = policy.timeout {
+                match timeout.checked_sub(started.elapsed()) {
+                    Some(remaining) if !remaining.is_zero() => wait = wait.min(remaining),
+                    _ => return Err(Error::Timeout),
+                }
+            }
+            Delay::new(wait).await;
+
+            interval = Duration::try_from_secs_f64(
+                interval.as_secs_f64() * policy.backoff_factor.max(1.0),
+            )
+            .unwrap_or(policy.max_interval)
+            .min(policy.max_interval);
+        }
+    }
+
     /// Get a list of jobs. This includes the job config, redacted metadata and status, but does NOT include the result.
     ///
     /// Setting the limit allows controlling the number of jobs returned.
@@ -161,7 +623,13 @@ impl BatchClient {
     /// use speechmatics::batch::BatchClient;
     ///
     /// let batch_client = BatchClient::new("API_KEY", None).unwrap();
-    /// let jobs = batch_client.get_jobs(Some(5), Some(true)).await.unwrap();
+    /// let jobs = batch_client.get_jobs(Some(5), Some(true), None).await.unwrap();
+    /// if let Some(last) = jobs.jobs.last() {
+    ///     let next_page = batch_client
+    ///         .get_jobs(Some(5), Some(true), Some(last.created_at.as_str()))
+    ///         .await
+    ///         .unwrap();
+    /// }
     /// ```
     ///
     /// # Errors
@@ -173,6 +641,7 @@ impl BatchClient {
         &self,
         limit: Option<i32>,
         include_deleted: Option<bool>,
+        created_before: Option<&str>,
     ) -> Result<RetrieveJobsResponse> {
         let url = self.batch_url.join("jobs")?;
 
@@ -186,13 +655,88 @@ impl BatchClient {
             queries.push(("include_deleted".to_owned(), format!("{}", del)))
         }
 
-        let res = self.client.get(url).query(&queries).send().await;
-        let result = res?.error_for_status()?.bytes().await?;
+        if let Some(before) = created_before {
+            queries.push(("created_before".to_owned(), before.to_owned()))
+        }
+
+        let res = self
+            .request(Method::GET, url)
+            .await?
+            .query(&queries)
+            .send()
+            .await;
+        let result = check_response(res?).await?.bytes().await?;
 
         let serde_res = serde_json::from_slice::<RetrieveJobsResponse>(&result)?;
         Ok(serde_res)
     }
 
+    /// Returns a stream of all the jobs matching the filter, from the most recent to the oldest.
+    ///
+    /// Jobs are fetched a page at a time using get_jobs, with the created_at of the last job on each page
+    /// as the cursor for the next. The next page is only requested once the current one has been consumed,
+    /// so the stream can be stopped early without fetching every job.
+    ///
+    /// As created_before is exclusive, the cursor is moved a millisecond past the last job, and jobs that were already
+    /// returned are dropped from the next page, so that jobs created at the same time as the last job aren't skipped.
+    /// The API can't page through more than a page of jobs created within the same millisecond, so if that happens
+    /// the rest of those jobs are skipped, and a warning is logged.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() {
+    /// use futures::TryStreamExt;
+    /// use speechmatics::batch::{BatchClient, JobsFilter};
+    ///
+    /// let batch_client = BatchClient::new("API_KEY", None).unwrap();
+    /// let jobs: Vec<_> = batch_client
+    ///     .jobs_stream(JobsFilter::default())
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Any error from get_jobs is yielded by the stream, after which the stream ends.
+    ///
+    pub fn jobs_stream(&self, filter: JobsFilter) -> impl Stream<Item = Result<JobDetails>> + '_ {
+        let page_size = filter.page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+        let include_deleted = filter.include_deleted;
+        let start = JobsCursor {
+            created_before: filter.created_before,
+            seen: HashSet::new(),
+        };
+        stream::try_unfold(Some(start), move |cursor| async move {
+            let Some(cursor) = cursor else {
+                return Ok(None);
+            };
+            let jobs = self
+                .get_jobs(
+                    Some(page_size),
+                    include_deleted,
+                    cursor.created_before.as_deref(),
+                )
+                .await?
+                .jobs;
+            // a page shorter than the page size is the last one
+            let next_cursor = match jobs.last() {
+                Some(last) if jobs.len() >= page_size as usize => {
+                    Some(cursor.next(&jobs, &last.created_at))
+                }
+                _ => None,
+            };
+            let jobs: Vec<_> = jobs
+                .into_iter()
+                .filter(|job| !cursor.seen.contains(&job.id))
+                .collect();
+            Ok::<_, Error>(Some((stream::iter(jobs.into_iter().map(Ok)), next_cursor)))
+        })
+        .try_flatten()
+    }
+
     /// Gets the json-formatted result of a batch job.
     /// This will include all the requested results (e.g. transcript, translation, summary) as well as config and metadata.
     ///
@@ -215,20 +759,12 @@ impl BatchClient {
     /// A common failure state occurs when requesting a result for an incomplete job.
     /// To this end, you should implement polling based on the get_job method
     /// to check the job status of a recently submitted job.
+    /// This failure can be detected by checking the error_response of the returned error for a TranscriptionNotReady error.
     ///
     pub async fn get_json_result(&self, job_id: &str) -> Result<RetrieveTranscriptResponse> {
-        let url = self
-            .batch_url
-            .join("jobs/")?
-            .join(&format!("{}/", job_id))?
-            .join("transcript")?;
-
-        let mut queries = self.default_query.clone();
-
-        queries.push(("format".to_owned(), "json-v2".to_owned()));
-
-        let res = self.client.get(url).query(&queries).send().await;
-        let result = res?.error_for_status()?.bytes().await?;
+        let result = self
+            .get_transcript_bytes(job_id, TranscriptFormat::JsonV2)
+            .await?;
 
         let serde_res = serde_json::from_slice::<RetrieveTranscriptResponse>(&result)?;
         Ok(serde_res)
@@ -258,18 +794,9 @@ impl BatchClient {
     /// to check the job status of a recently submitted job.
     ///
     pub async fn get_text_result(&self, job_id: &str) -> Result<String> {
-        let url = self
-            .batch_url
-            .join("jobs/")?
-            .join(&format!("{}/", job_id))?
-            .join("transcript")?;
-
-        let mut queries = self.default_query.clone();
-
-        queries.push(("format".to_owned(), "txt".to_owned()));
-
-        let res = self.client.get(url).query(&queries).send().await;
-        let result = res?.error_for_status()?.bytes().await?;
+        let result = self
+            .get_transcript_bytes(job_id, TranscriptFormat::Txt)
+            .await?;
 
         let serde_res = String::from_utf8(result.to_vec())?;
         Ok(serde_res)
@@ -277,6 +804,7 @@ impl BatchClient {
 
     /// Gets the SRT result of a batch job. This will be returned as a String.
     /// This will only include the transcript, without any meta data, translations or summary.
+    /// Use get_transcript with TranscriptFormat::Srt to get the parsed cues as well.
     ///
     /// # Example
     ///
@@ -285,7 +813,7 @@ impl BatchClient {
     /// use speechmatics::batch::BatchClient;
     ///
     /// let batch_client = BatchClient::new("API_KEY", None).unwrap();
-    /// let get_result_res = batch_client.get_text_result("JOB_ID").await.unwrap();
+    /// let get_result_res = batch_client.get_srt_result("JOB_ID").await.unwrap();
     /// println!("{:?}", get_result_res);
     /// ```
     ///
@@ -299,6 +827,74 @@ impl BatchClient {
     /// to check the job status of a recently submitted job.
     ///
     pub async fn get_srt_result(&self, job_id: &str) -> Result<String> {
+        let result = self
+            .get_transcript_bytes(job_id, TranscriptFormat::Srt)
+            .await?;
+
+        let serde_res = String::from_utf8(result.to_vec())?;
+        Ok(serde_res)
+    }
+
+    /// Gets the transcript of a batch job in the given format.
+    ///
+    /// For json-v2 the response is deserialised, and for srt the cues are parsed out of the raw file as well.
+    /// The lines of each cue are wrapped by the server, according to the srt_overrides in the output_config of the job.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() {
+    /// use speechmatics::batch::{
+    ///     transcript::{Transcript, TranscriptFormat},
+    ///     BatchClient,
+    /// };
+    ///
+    /// let batch_client = BatchClient::new("API_KEY", None).unwrap();
+    /// let transcript = batch_client
+    ///     .get_transcript("JOB_ID", TranscriptFormat::Srt)
+    ///     .await
+    ///     .unwrap();
+    /// if let Transcript::Srt(srt) = transcript {
+    ///     for cue in srt.cues {
+    ///         println!("{} --> {}: {}", cue.start_time, cue.end_time, cue.text());
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function can error with the usual HTTP status code errors.
+    /// It will also return Error::Decode if the transcript can't be parsed in the requested format.
+    ///
+    pub async fn get_transcript(
+        &self,
+        job_id: &str,
+        format: TranscriptFormat,
+    ) -> Result<Transcript> {
+        let result = self.get_transcript_bytes(job_id, format).await?;
+
+        let transcript = match format {
+            TranscriptFormat::JsonV2 => {
+                let json = serde_json::from_slice::<RetrieveTranscriptResponse>(&result)?;
+                Transcript::JsonV2(Box::new(json))
+            }
+            TranscriptFormat::Txt => Transcript::Txt(String::from_utf8(result.to_vec())?),
+            TranscriptFormat::Srt => {
+                let raw = String::from_utf8(result.to_vec())?;
+                let cues = transcript::parse_srt(&raw)?;
+                Transcript::Srt(SrtTranscript { raw, cues })
+            }
+        };
+        Ok(transcript)
+    }
+
+    /// Gets the raw body of the transcript of a batch job in the given format.
+    async fn get_transcript_bytes(
+        &self,
+        job_id: &str,
+        format: TranscriptFormat,
+    ) -> Result<bytes::Bytes> {
         let url = self
             .batch_url
             .join("jobs/")?
@@ -307,12 +903,209 @@ impl BatchClient {
 
         let mut queries = self.default_query.clone();
 
-        queries.push(("format".to_owned(), "srt".to_owned()));
+        queries.push(("format".to_owned(), format.to_string()));
 
-        let res = self.client.get(url).query(&queries).send().await;
-        let result = res?.error_for_status()?.bytes().await?;
+        let res = self
+            .request(Method::GET, url)
+            .await?
+            .query(&queries)
+            .send()
+            .await;
+        let result = check_response(res?).await?.bytes().await?;
+        Ok(result)
+    }
 
-        let serde_res = serde_json::from_slice::<String>(&result)?;
+    /// Gets the aligned text file of an alignment job, with timing information added in the format set by tags.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() {
+    /// use speechmatics::batch::{alignment::AlignmentTags, BatchClient};
+    ///
+    /// let batch_client = BatchClient::new("API_KEY", None).unwrap();
+    /// let aligned_text = batch_client
+    ///     .get_alignment("JOB_ID", AlignmentTags::OnePerLine)
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function can error with the usual HTTP status code errors.
+    /// A JobIsNotOfTypeAlignment error is returned if the job is a transcription job.
+    ///
+    pub async fn get_alignment(&self, job_id: &str, tags: AlignmentTags) -> Result<String> {
+        let url = self
+            .batch_url
+            .join("jobs/")?
+            .join(&format!("{}/", job_id))?
+            .join("alignment")?;
+
+        let mut queries = self.default_query.clone();
+
+        queries.push(("tags".to_owned(), tags.to_string()));
+
+        let res = self
+            .request(Method::GET, url)
+            .await?
+            .query(&queries)
+            .send()
+            .await;
+        let result = check_response(res?).await?.bytes().await?;
+
+        let serde_res = String::from_utf8(result.to_vec())?;
+        Ok(serde_res)
+    }
+
+    /// Gets the result of an alignment job as a list of words, each with a start and end time.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as get_alignment are possible, as well as Error::Decode if the tags can't be parsed.
+    ///
+    pub async fn get_aligned_words(&self, job_id: &str) -> Result<Vec<AlignedWord>> {
+        let text = self
+            .get_alignment(job_id, AlignmentTags::WordStartAndEnd)
+            .await?;
+        alignment::parse_word_start_and_end(&text)
+    }
+
+    /// Gets the result of an alignment job as a list of lines, each with a start time.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as get_alignment are possible, as well as Error::Decode if the tags can't be parsed.
+    ///
+    pub async fn get_aligned_lines(&self, job_id: &str) -> Result<Vec<AlignedLine>> {
+        let text = self
+            .get_alignment(job_id, AlignmentTags::OnePerLine)
+            .await?;
+        alignment::parse_one_per_line(&text)
+    }
+
+    /// Transcribes a file in one go: it submits the job, waits for it to finish and then fetches the json-formatted result.
+    ///
+    /// The poll_policy controls how the job status is polled, and defaults to PollPolicy::default().
+    /// If delete_job is set to true, the job is deleted once the result has been fetched, or if anything goes wrong after submission.
+    /// The default is false.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() {
+    /// use std::path::PathBuf;
+    /// use speechmatics::batch::{
+    ///     BatchClient,
+    ///     models::{JobConfig, TranscriptionConfig}
+    /// };
+    ///
+    /// let batch_client = BatchClient::new("API_KEY", None).unwrap();
+    ///
+    /// let mut config = JobConfig::default();
+    /// let transcription_config = TranscriptionConfig::new("en".to_owned());
+    /// config.transcription_config = Some(Box::new(transcription_config));
+    ///
+    /// let transcript = batch_client
+    ///     .transcribe(config, PathBuf::from("example.wav"), None, Some(true))
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Any of the errors from submit_job, wait_for_job and get_json_result can be returned.
+    /// If deleting the job fails after a successful transcription, the delete error is returned.
+    /// If the transcription itself failed, that error is returned instead and the delete error is only logged.
+    ///
+    pub async fn transcribe(
+        &self,
+        config: JobConfig,
+        file: impl Into<JobFile>,
+        poll_policy: Option<PollPolicy>,
+        delete_job: Option<bool>,
+    ) -> Result<RetrieveTranscriptResponse> {
+        let job = self.submit_job(config, file).await?;
+        let result = match self
+            .wait_for_job(&job.id, poll_policy.unwrap_or_default())
+            .await
+        {
+            Ok(_) => self.get_json_result(&job.id).await,
+            Err(err) => Err(err),
+        };
+
+        if delete_job.unwrap_or(false) {
+            // force is needed in case the job is still running, e.g. after a timeout
+            let delete_res = self.delete_job(&job.id, Some(true)).await;
+            match (&result, delete_res) {
+                (Ok(_), Err(err)) => return Err(err),
+                (Err(_), Err(err)) => warn!("failed to delete job {}: {}", job.id, err),
+                _ => {}
+            }
+        }
+        result
+    }
+
+    /// Get the usage statistics for the account, including the number and duration of billable jobs.
+    ///
+    /// Setting since and until limits the usage to jobs between the given dates (both inclusive).
+    /// If they are not set, the server returns usage for the current billing cycle.
+    ///
+    /// The usage::aggregate_usage helper can be used to total the returned details by job type, operating point or language.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() {
+    /// use chrono::NaiveDate;
+    /// use speechmatics::batch::BatchClient;
+    ///
+    /// let batch_client = BatchClient::new("API_KEY", None).unwrap();
+    /// let since = NaiveDate::from_ymd_opt(2023, 9, 1);
+    /// let until = NaiveDate::from_ymd_opt(2023, 9, 30);
+    /// let usage = batch_client.get_usage(since, until).await.unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function can error with the usual HTTP status code errors.
+    /// It will also error if it fails to parse the server response for whatever reason.
+    ///
+    pub async fn get_usage(
+        &self,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> Result<UsageResponse> {
+        let url = self.batch_url.join("usage")?;
+
+        let mut queries = self.default_query.clone();
+
+        if let Some(since_date) = since {
+            queries.push((
+                "since".to_owned(),
+                since_date.format("%Y-%m-%d").to_string(),
+            ))
+        }
+
+        if let Some(until_date) = until {
+            queries.push((
+                "until".to_owned(),
+                until_date.format("%Y-%m-%d").to_string(),
+            ))
+        }
+
+        let res = self
+            .request(Method::GET, url)
+            .await?
+            .query(&queries)
+            .send()
+            .await;
+        let result = check_response(res?).await?.bytes().await?;
+
+        let serde_res = serde_json::from_slice::<UsageResponse>(&result)?;
         Ok(serde_res)
     }
 
@@ -348,18 +1141,78 @@ impl BatchClient {
             queries.push(("force".to_owned(), format!("{}", force_set)))
         }
 
-        let res = self.client.delete(url).query(&queries).send().await;
-        let result = res?.error_for_status()?.bytes().await?;
+        let res = self
+            .request(Method::DELETE, url)
+            .await?
+            .query(&queries)
+            .send()
+            .await;
+        let result = check_response(res?).await?.bytes().await?;
 
         let serde_res = serde_json::from_slice::<DeleteJobResponse>(&result)?;
         Ok(serde_res)
     }
 }
 
+/// Checks that a DataFetchConfig is present and has a valid http(s) URL and auth headers.
+fn validate_fetch_config(name: &str, fetch_config: Option<&DataFetchConfig>) -> Result<()> {
+    let fetch_config =
+        fetch_config.ok_or_else(|| Error::InvalidInput(format!("{} must be set", name)))?;
+
+    let url = Url::parse(&fetch_config.url)
+        .map_err(|err| Error::InvalidInput(format!("{} has an invalid url: {}", name, err)))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(Error::InvalidInput(format!(
+            "{} url must be http or https, not {}",
+            name,
+            url.scheme()
+        )));
+    }
+
+    for header in fetch_config.auth_headers.iter().flatten() {
+        match header.split_once(':') {
+            Some((header_name, _)) if !header_name.trim().is_empty() => {}
+            _ => {
+                return Err(Error::InvalidInput(format!(
+                    "{} auth header {} must be of the form `Name: value`",
+                    name, header
+                )))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks the status of a response, passing it through if it was successful.
+/// Otherwise the body is parsed into an ErrorResponse and returned as an error.
+/// If the body isn't a valid ErrorResponse, the status and raw body are returned instead.
+async fn check_response(res: Response) -> Result<Response> {
+    let status = res.status();
+    if status.is_success() {
+        return Ok(res);
+    }
+    let body = res.bytes().await?;
+    Err(Error::from_batch_response(status.as_u16(), &body))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::ApiError;
+    use futures::StreamExt;
     use std::path::PathBuf;
+    use wiremock::{
+        matchers::{header, method, path, query_param, query_param_is_missing},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    /// Creates a client pointed at a mock server, so that responses can be tested without hitting the API
+    async fn mock_client() -> (BatchClient, MockServer) {
+        let mock_server = MockServer::start().await;
+        let url = Url::parse(&format!("{}/v2/", mock_server.uri())).unwrap();
+        let batch_client = BatchClient::new("API_KEY", Some(url)).unwrap();
+        (batch_client, mock_server)
+    }
 
     async fn submit_job_util(batch_client: &BatchClient) -> Result<CreateJobResponse> {
         let test_file_path = PathBuf::new()
@@ -378,15 +1231,595 @@ mod tests {
 
     #[tokio::test]
     async fn test_not_authorised() {
-        let batch_client = BatchClient::new("blah", None).unwrap();
+        let (batch_client, mock_server) = mock_client().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/jobs"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+                "code": 401,
+                "error": "Unauthorized"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let err = submit_job_util(&batch_client).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Auth(ApiError::Http { status: 401, .. })
+        ));
+    }
 
-        let job_res = submit_job_util(&batch_client).await;
-        match job_res {
-            Ok(_) => panic!("Something went wrong with auth"),
-            Err(err) => {
-                assert!(err.is::<reqwest::Error>())
+    #[tokio::test]
+    async fn test_error_response_parsed() {
+        let (batch_client, mock_server) = mock_client().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs/JOB_ID/transcript"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "code": 404,
+                "error": "Transcription not ready",
+                "detail": "Job is still running"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let err = batch_client.get_json_result("JOB_ID").await.unwrap_err();
+        assert_eq!(err.status(), Some(404));
+        let error_response = err.error_response().unwrap();
+        assert_eq!(
+            error_response.error,
+            models::error_response::Error::TranscriptionNotReady
+        );
+        assert_eq!(
+            error_response.detail.as_deref(),
+            Some("Job is still running")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_error_response() {
+        let (batch_client, mock_server) = mock_client().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs/JOB_ID"))
+            .respond_with(ResponseTemplate::new(429).set_body_string("Too Many Requests"))
+            .mount(&mock_server)
+            .await;
+
+        let err = batch_client.get_job("JOB_ID").await.unwrap_err();
+        assert_eq!(err.status(), Some(429));
+        assert!(err.error_response().is_none());
+        match err {
+            Error::Api(ApiError::Http { status, body }) => {
+                assert_eq!(status, 429);
+                assert_eq!(body, "Too Many Requests");
+            }
+            err => panic!("unexpected error {:?}", err),
+        }
+    }
+
+    fn job_response(status: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "job": {
+                "created_at": "2023-10-25T09:00:00.000Z",
+                "data_name": "example.wav",
+                "id": "JOB_ID",
+                "status": status,
+                "errors": [{"timestamp": "2023-10-25T09:00:01.000Z", "message": "Unable to fetch audio"}]
+            }
+        }))
+    }
+
+    fn fast_poll_policy() -> PollPolicy {
+        PollPolicy {
+            initial_interval: Duration::from_millis(10),
+            max_interval: Duration::from_millis(20),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_builder() {
+        let mock_server = MockServer::start().await;
+        let url = Url::parse(&format!("{}/v2/", mock_server.uri())).unwrap();
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs/JOB_ID"))
+            .and(header("Authorization", "Bearer API_KEY"))
+            .and(header("X-Request-Source", "test"))
+            .respond_with(job_response("done"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs/SLOW_JOB_ID"))
+            .respond_with(job_response("done").set_delay(Duration::from_secs(5)))
+            .mount(&mock_server)
+            .await;
+
+        let batch_client = BatchClient::builder()
+            .api_key("API_KEY")
+            .url(url.clone())
+            .header("X-Request-Source", "test")
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+        batch_client.get_job("JOB_ID").await.unwrap();
+        let err = batch_client.get_job("SLOW_JOB_ID").await.unwrap_err();
+        assert!(matches!(err, Error::Timeout));
+
+        let err = BatchClient::builder().url(url.clone()).build().err();
+        assert!(matches!(err, Some(Error::InvalidInput(_))));
+        let err = BatchClient::builder()
+            .api_key("API_KEY")
+            .header("Invalid Header", "test")
+            .build()
+            .err();
+        assert!(matches!(err, Some(Error::InvalidInput(_))));
+        let err = BatchClient::builder()
+            .api_key("API_KEY")
+            .proxy(Url::parse("socks5://127.0.0.1:1080").unwrap())
+            .build()
+            .err();
+        assert!(matches!(err, Some(Error::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_job_done() {
+        let (batch_client, mock_server) = mock_client().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs/JOB_ID"))
+            .respond_with(job_response("running"))
+            .up_to_n_times(3)
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs/JOB_ID"))
+            .respond_with(job_response("done"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let job = batch_client
+            .wait_for_job("JOB_ID", fast_poll_policy())
+            .await
+            .unwrap();
+        assert_eq!(job.status, models::job_details::Status::Done);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_job_rejected() {
+        let (batch_client, mock_server) = mock_client().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs/JOB_ID"))
+            .respond_with(job_response("rejected"))
+            .mount(&mock_server)
+            .await;
+
+        let err = batch_client
+            .wait_for_job("JOB_ID", fast_poll_policy())
+            .await
+            .unwrap_err();
+        match err {
+            Error::JobFailed(job) => {
+                assert_eq!(job.status, models::job_details::Status::Rejected);
+                assert_eq!(job.errors.unwrap()[0].message, "Unable to fetch audio");
+            }
+            err => panic!("unexpected error {:?}", err),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_job_timeout_and_cancel() {
+        let (batch_client, mock_server) = mock_client().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs/JOB_ID"))
+            .respond_with(job_response("running"))
+            .mount(&mock_server)
+            .await;
+
+        let policy = PollPolicy {
+            timeout: Some(Duration::from_millis(50)),
+            ..fast_poll_policy()
+        };
+        let err = batch_client.wait_for_job("JOB_ID", policy).await;
+        assert!(matches!(err, Err(Error::Timeout)));
+
+        let cancel = Delay::new(Duration::from_millis(50));
+        let err = batch_client
+            .wait_for_job_with_cancel("JOB_ID", fast_poll_policy(), cancel)
+            .await;
+        assert!(matches!(err, Err(Error::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_transcribe() {
+        let (batch_client, mock_server) = mock_client().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/jobs"))
+            .respond_with(
+                ResponseTemplate::new(201).set_body_json(serde_json::json!({"id": "JOB_ID"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs/JOB_ID"))
+            .respond_with(job_response("done"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs/JOB_ID/transcript"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "format": "2.9",
+                "job": {
+                    "created_at": "2023-10-25T09:00:00.000Z",
+                    "data_name": "example.wav",
+                    "duration": 4,
+                    "id": "JOB_ID"
+                },
+                "metadata": {
+                    "created_at": "2023-10-25T09:00:05.000Z",
+                    "type": "transcription"
+                },
+                "results": [{
+                    "start_time": 0.1,
+                    "end_time": 0.5,
+                    "type": "word",
+                    "alternatives": [{"content": "hello", "confidence": 1.0, "language": "en"}]
+                }]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/v2/jobs/JOB_ID"))
+            .respond_with(job_response("deleted"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let test_file_path = PathBuf::new()
+            .join(".")
+            .join("tests")
+            .join("data")
+            .join("example.wav");
+        let transcript = batch_client
+            .transcribe(
+                JobConfig::default(),
+                test_file_path,
+                Some(fast_poll_policy()),
+                Some(true),
+            )
+            .await
+            .unwrap();
+        assert_eq!(transcript.job.data_name, "example.wav");
+        assert_eq!(transcript.results.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_usage() {
+        let (batch_client, mock_server) = mock_client().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/usage"))
+            .and(query_param("since", "2023-09-01"))
+            .and(query_param("until", "2023-09-30"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "since": "2023-09-01T00:00:00Z",
+                "until": "2023-09-30T23:59:59Z",
+                "summary": [{"mode": "batch", "type": "transcription", "count": 5, "duration_hrs": 1.53}],
+                "details": [{
+                    "mode": "batch",
+                    "type": "transcription",
+                    "language": "en",
+                    "operating_point": "enhanced",
+                    "count": 5,
+                    "duration_hrs": 1.53
+                }]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let usage = batch_client
+            .get_usage(
+                NaiveDate::from_ymd_opt(2023, 9, 1),
+                NaiveDate::from_ymd_opt(2023, 9, 30),
+            )
+            .await
+            .unwrap();
+        assert_eq!(usage.details.len(), 1);
+        assert_eq!(
+            usage.details[0].operating_point,
+            Some(models::OperatingPoint::Enhanced)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_jobs_stream() {
+        fn jobs_page(jobs: &[(&str, &str)]) -> ResponseTemplate {
+            let jobs: Vec<_> = jobs
+                .iter()
+                .map(|(id, second)| {
+                    serde_json::json!({
+                        "created_at": format!("2024-01-01T00:00:0{}.000Z", second),
+                        "data_name": "example.wav",
+                        "id": id,
+                        "status": "done"
+                    })
+                })
+                .collect();
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "jobs": jobs }))
+        }
+
+        let (batch_client, mock_server) = mock_client().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs"))
+            .and(query_param("limit", "3"))
+            .and(query_param_is_missing("created_before"))
+            .respond_with(jobs_page(&[
+                ("JOB_5", "5"),
+                ("JOB_4", "4"),
+                ("JOB_3A", "3"),
+            ]))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        // JOB_3B was created at the same time as the last job on the first page, so the cursor is moved past it
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs"))
+            .and(query_param("created_before", "2024-01-01T00:00:03.001Z"))
+            .respond_with(jobs_page(&[
+                ("JOB_3A", "3"),
+                ("JOB_3B", "3"),
+                ("JOB_2", "2"),
+            ]))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs"))
+            .and(query_param("created_before", "2024-01-01T00:00:02.001Z"))
+            .respond_with(jobs_page(&[("JOB_2", "2"), ("JOB_1", "1")]))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let filter = JobsFilter {
+            page_size: Some(3),
+            ..Default::default()
+        };
+        let ids: Vec<String> = batch_client
+            .jobs_stream(filter.clone())
+            .map_ok(|job| job.id)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(
+            ids,
+            vec!["JOB_5", "JOB_4", "JOB_3A", "JOB_3B", "JOB_2", "JOB_1"]
+        );
+
+        // taking the first page only should not request the second
+        let first: Vec<_> = batch_client.jobs_stream(filter).take(3).collect().await;
+        assert_eq!(first.len(), 3);
+
+        // a full page created within the same millisecond can only be skipped past
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs"))
+            .and(query_param("created_before", "2024-01-01T00:00:09.000Z"))
+            .respond_with(jobs_page(&[("JOB_8A", "8"), ("JOB_8B", "8")]))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs"))
+            .and(query_param("created_before", "2024-01-01T00:00:08.001Z"))
+            .respond_with(jobs_page(&[("JOB_8A", "8"), ("JOB_8B", "8")]))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs"))
+            .and(query_param("created_before", "2024-01-01T00:00:08.000Z"))
+            .respond_with(jobs_page(&[("JOB_7", "7")]))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let filter = JobsFilter {
+            page_size: Some(2),
+            created_before: Some("2024-01-01T00:00:09.000Z".to_owned()),
+            ..Default::default()
+        };
+        let ids: Vec<String> = batch_client
+            .jobs_stream(filter)
+            .map_ok(|job| job.id)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(ids, vec!["JOB_8A", "JOB_8B", "JOB_7"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_transcript() {
+        let srt = "1\n00:00:00,480 --> 00:00:02,100\nHello world.\n";
+        let (batch_client, mock_server) = mock_client().await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs/JOB_ID/transcript"))
+            .and(query_param("format", "srt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(srt))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs/JOB_ID/transcript"))
+            .and(query_param("format", "txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("Hello world."))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_eq!(batch_client.get_srt_result("JOB_ID").await.unwrap(), srt);
+
+        match batch_client
+            .get_transcript("JOB_ID", TranscriptFormat::Srt)
+            .await
+            .unwrap()
+        {
+            Transcript::Srt(transcript) => {
+                assert_eq!(transcript.raw, srt);
+                assert_eq!(transcript.cues.len(), 1);
+                assert_eq!(transcript.cues[0].text(), "Hello world.");
             }
+            transcript => panic!("unexpected transcript {:?}", transcript),
+        }
+
+        let transcript = batch_client
+            .get_transcript("JOB_ID", TranscriptFormat::Txt)
+            .await
+            .unwrap();
+        assert_eq!(transcript, Transcript::Txt("Hello world.".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn test_alignment_job() {
+        let (batch_client, mock_server) = mock_client().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/jobs"))
+            .and(|req: &wiremock::Request| {
+                let needle = b"name=\"text_file\"; filename=\"Cargo.toml\"";
+                req.body.windows(needle.len()).any(|part| part == needle)
+            })
+            .respond_with(
+                ResponseTemplate::new(201).set_body_json(serde_json::json!({"id": "JOB_ID"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/jobs/JOB_ID/alignment"))
+            .and(query_param("tags", "word_start_and_end"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("<time=0.41>hello<time=0.76> <time=0.89>world<time=1.18>"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let test_file_path = PathBuf::new()
+            .join(".")
+            .join("tests")
+            .join("data")
+            .join("example.wav");
+        let text_file_path = PathBuf::new().join(".").join("Cargo.toml");
+
+        let transcription_config = JobConfig::default();
+        let err = batch_client
+            .submit_alignment_job(
+                transcription_config,
+                test_file_path.clone(),
+                text_file_path.clone(),
+            )
+            .await;
+        assert!(matches!(err, Err(Error::InvalidInput(_))));
+
+        let mut config = JobConfig::new(JobType::Alignment);
+        config.alignment_config = Some(Box::new(AlignmentConfig::new("en".to_owned())));
+        let job_res = batch_client
+            .submit_alignment_job(config, test_file_path, text_file_path)
+            .await
+            .unwrap();
+
+        let words = batch_client.get_aligned_words(&job_res.id).await.unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[1].content, "world");
+        assert_eq!(words[1].end_time, 1.18);
+    }
+
+    #[tokio::test]
+    async fn test_submit_fetch_job() {
+        let (batch_client, mock_server) = mock_client().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/jobs"))
+            .and(|req: &wiremock::Request| {
+                let body = String::from_utf8_lossy(&req.body);
+                body.contains("https://example.com/audio.wav") && !body.contains("data_file")
+            })
+            .respond_with(
+                ResponseTemplate::new(201).set_body_json(serde_json::json!({"id": "JOB_ID"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut config = JobConfig::default();
+        let err = batch_client.submit_fetch_job(config.clone()).await;
+        assert!(matches!(err, Err(Error::InvalidInput(_))));
+
+        let mut fetch_data = DataFetchConfig::new("s3://bucket/audio.wav".to_owned());
+        config.fetch_data = Some(Box::new(fetch_data.clone()));
+        let err = batch_client.submit_fetch_job(config.clone()).await;
+        assert!(matches!(err, Err(Error::InvalidInput(_))));
+
+        fetch_data.url = "https://example.com/audio.wav".to_owned();
+        fetch_data.auth_headers = Some(vec!["Bearer TOKEN".to_owned()]);
+        config.fetch_data = Some(Box::new(fetch_data.clone()));
+        let err = batch_client.submit_fetch_job(config.clone()).await;
+        assert!(matches!(err, Err(Error::InvalidInput(_))));
+
+        fetch_data.auth_headers = Some(vec!["Authorization: Bearer TOKEN".to_owned()]);
+        config.fetch_data = Some(Box::new(fetch_data.clone()));
+        let mut alignment_config = config.clone();
+        alignment_config.type_value = JobType::Alignment;
+        let err = batch_client.submit_fetch_job(alignment_config).await;
+        assert!(matches!(err, Err(Error::InvalidInput(_))));
+
+        let job_res = batch_client.submit_fetch_job(config).await.unwrap();
+        assert_eq!(job_res.id, "JOB_ID");
+    }
+
+    #[tokio::test]
+    async fn test_submit_streamed_job() {
+        let (batch_client, mock_server) = mock_client().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/jobs"))
+            .and(|req: &wiremock::Request| {
+                let body = String::from_utf8_lossy(&req.body);
+                body.contains("filename=\"streamed.wav\"") && body.contains("streamed audio data")
+            })
+            .respond_with(
+                ResponseTemplate::new(201).set_body_json(serde_json::json!({"id": "JOB_ID"})),
+            )
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let data = b"streamed audio data".to_vec();
+        let files = vec![
+            JobFile::from_bytes("streamed.wav", data.clone()),
+            JobFile::from_reader(
+                "streamed.wav",
+                futures::io::Cursor::new(data.clone()),
+                Some(data.len() as u64),
+            ),
+            JobFile::from_stream(
+                "streamed.wav",
+                futures::stream::iter(
+                    data.chunks(4)
+                        .map(|chunk| Ok::<_, std::io::Error>(chunk.to_vec()))
+                        .collect::<Vec<_>>(),
+                ),
+                None,
+            ),
+        ];
+        for file in files {
+            let job_res = batch_client
+                .submit_job(JobConfig::default(), file)
+                .await
+                .unwrap();
+            assert_eq!(job_res.id, "JOB_ID");
         }
+
+        let err = batch_client
+            .submit_job(JobConfig::default(), PathBuf::from("does-not-exist.wav"))
+            .await;
+        assert!(matches!(err, Err(Error::AudioRead(_))));
     }
 
     #[tokio::test]
@@ -421,7 +1854,7 @@ mod tests {
         let _ = submit_job_util(&batch_client).await.unwrap();
         let _ = submit_job_util(&batch_client).await.unwrap();
         let _ = submit_job_util(&batch_client).await.unwrap();
-        let job_res = batch_client.get_jobs(Some(2), None).await.unwrap();
+        let job_res = batch_client.get_jobs(Some(2), None, None).await.unwrap();
         assert!(job_res.jobs.len() == 2)
     }
 
@@ -431,22 +1864,14 @@ mod tests {
         let batch_client = BatchClient::new(&api_key, None).unwrap();
 
         let job_res = submit_job_util(&batch_client).await.unwrap();
-        let mut success = false;
-        let mut retries = 0;
-        while !success {
-            let get_job_res = batch_client.get_job(&job_res.id).await.unwrap();
-            if get_job_res.job.status == models::job_details::Status::Done {
-                success = true
-            } else if get_job_res.job.status != models::job_details::Status::Running {
-                panic!("Job failed");
-            } else {
-                if retries > 6 {
-                    panic!("Job took too long to complete");
-                }
-                retries += 1;
-                std::thread::sleep(std::time::Duration::from_millis(3000));
-            }
-        }
+        let policy = PollPolicy {
+            timeout: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+        batch_client
+            .wait_for_job(&job_res.id, policy)
+            .await
+            .unwrap();
         let get_result_res = batch_client.get_json_result(&job_res.id).await.unwrap();
         assert!(get_result_res.job.data_name == "example.wav");
         assert!(get_result_res.results.len() != 0)
@@ -458,22 +1883,14 @@ mod tests {
         let batch_client = BatchClient::new(&api_key, None).unwrap();
 
         let job_res = submit_job_util(&batch_client).await.unwrap();
-        let mut success = false;
-        let mut retries = 0;
-        while !success {
-            let get_job_res = batch_client.get_job(&job_res.id).await.unwrap();
-            if get_job_res.job.status == models::job_details::Status::Done {
-                success = true
-            } else if get_job_res.job.status != models::job_details::Status::Running {
-                panic!("Job failed");
-            } else {
-                if retries > 6 {
-                    panic!("Job took too long to complete");
-                }
-                retries += 1;
-                std::thread::sleep(std::time::Duration::from_millis(3000));
-            }
-        }
+        let policy = PollPolicy {
+            timeout: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+        batch_client
+            .wait_for_job(&job_res.id, policy)
+            .await
+            .unwrap();
         let get_result_res = batch_client.get_text_result(&job_res.id).await.unwrap();
         assert!(get_result_res.len() != 0)
     }
@@ -0,0 +1,424 @@
+//! Renders the results of a finished job into subtitles locally, so they can be reformatted without re-running the job.
+//!
+//! Words are grouped into cues of up to max_lines lines of max_line_length characters, and a new cue is started at the
+//! end of each sentence, when the speaker changes, or when the cue would be longer than max_cue_duration.
+
+use super::models::{
+    recognition_result::Type, OutputConfigSrtOverrides, RecognitionResult,
+    RetrieveTranscriptResponse,
+};
+use super::transcript::SubtitleCue;
+use crate::punctuation::Attachment;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// The subtitle formats that can be rendered.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum SubtitleFormat {
+    /// SubRip subtitles. This is the default.
+    #[default]
+    Srt,
+    /// WebVTT subtitles, with speakers marked using voice tags.
+    WebVtt,
+    /// Timed Text Markup Language subtitles.
+    Ttml,
+}
+
+/// SubtitleOptions controls how results are split into cues.
+///
+/// It implements default, which matches the defaults of the SRT output of the batch API:
+/// 2 lines of 37 characters per cue, with cues lasting up to 6 seconds and speaker labels included.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubtitleOptions {
+    /// The maximum number of characters per line, including white space and the speaker label.
+    /// Words longer than this are put on a line of their own.
+    pub max_line_length: usize,
+    /// The maximum number of lines per cue.
+    pub max_lines: usize,
+    /// The maximum time a cue can be shown for. A cue with a single word may exceed this if the word does.
+    pub max_cue_duration: Duration,
+    /// Whether to label each cue with its speaker, or its channel for channel diarization. Results without a speaker or channel are never labelled.
+    pub speaker_labels: bool,
+}
+
+impl Default for SubtitleOptions {
+    fn default() -> Self {
+        Self {
+            max_line_length: 37,
+            max_lines: 2,
+            max_cue_duration: Duration::from_secs(6),
+            speaker_labels: true,
+        }
+    }
+}
+
+impl From<&OutputConfigSrtOverrides> for SubtitleOptions {
+    fn from(overrides: &OutputConfigSrtOverrides) -> Self {
+        let mut options = Self::default();
+        if let Some(max_line_length) = overrides.max_line_length.filter(|len| *len > 0) {
+            options.max_line_length = max_line_length as usize;
+        }
+        if let Some(max_lines) = overrides.max_lines.filter(|lines| *lines > 0) {
+            options.max_lines = max_lines as usize;
+        }
+        options
+    }
+}
+
+/// Renders the results of a transcript into subtitles in the given format.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn run() {
+/// use speechmatics::batch::{
+///     subtitles::{render_subtitles, SubtitleFormat, SubtitleOptions},
+///     BatchClient,
+/// };
+///
+/// let batch_client = BatchClient::new("API_KEY", None).unwrap();
+/// let transcript = batch_client.get_json_result("JOB_ID").await.unwrap();
+///
+/// let options = SubtitleOptions {
+///     max_line_length: 32,
+///     ..Default::default()
+/// };
+/// let vtt = render_subtitles(&transcript, SubtitleFormat::WebVtt, &options);
+/// # }
+/// ```
+///
+pub fn render_subtitles(
+    transcript: &RetrieveTranscriptResponse,
+    format: SubtitleFormat,
+    options: &SubtitleOptions,
+) -> String {
+    let cues = subtitle_cues(&transcript.results, options);
+    let language = transcript
+        .metadata
+        .transcription_config
+        .as_ref()
+        .map(|config| config.language.as_str());
+    render_cues(&cues, format, language)
+}
+
+/// Renders a list of cues into subtitles in the given format. The language is only used by TTML.
+pub fn render_cues(cues: &[SubtitleCue], format: SubtitleFormat, language: Option<&str>) -> String {
+    let mut out = String::new();
+    match format {
+        SubtitleFormat::Srt => {
+            for cue in cues {
+                let _ = writeln!(
+                    out,
+                    "{}\n{} --> {}",
+                    cue.index,
+                    timestamp(cue.start_time, ','),
+                    timestamp(cue.end_time, ',')
+                );
+                for (i, line) in cue.lines.iter().enumerate() {
+                    match &cue.speaker {
+                        Some(speaker) if i == 0 => {
+                            let _ = writeln!(out, "{}: {}", speaker, line);
+                        }
+                        _ => {
+                            let _ = writeln!(out, "{}", line);
+                        }
+                    }
+                }
+                out.push('\n');
+            }
+        }
+        SubtitleFormat::WebVtt => {
+            out.push_str("WEBVTT\n\n");
+            for cue in cues {
+                let _ = writeln!(
+                    out,
+                    "{}\n{} --> {}",
+                    cue.index,
+                    timestamp(cue.start_time, '.'),
+                    timestamp(cue.end_time, '.')
+                );
+                for (i, line) in cue.lines.iter().enumerate() {
+                    match &cue.speaker {
+                        Some(speaker) if i == 0 => {
+                            let _ = writeln!(out, "<v {}>{}", escape(speaker), escape(line));
+                        }
+                        _ => {
+                            let _ = writeln!(out, "{}", escape(line));
+                        }
+                    }
+                }
+                out.push('\n');
+            }
+        }
+        SubtitleFormat::Ttml => {
+            out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+            match language {
+                Some(language) => {
+                    let _ = writeln!(
+                        out,
+                        "<tt xmlns=\"http://www.w3.org/ns/ttml\" xml:lang=\"{}\">",
+                        escape(language)
+                    );
+                }
+                None => out.push_str("<tt xmlns=\"http://www.w3.org/ns/ttml\">\n"),
+            }
+            out.push_str("  <body>\n    <div>\n");
+            for cue in cues {
+                let mut lines: Vec<String> = cue.lines.iter().map(|line| escape(line)).collect();
+                if let (Some(speaker), Some(first)) = (&cue.speaker, lines.first_mut()) {
+                    *first = format!("{}: {}", escape(speaker), first);
+                }
+                let _ = writeln!(
+                    out,
+                    "      <p begin=\"{}\" end=\"{}\">{}</p>",
+                    timestamp(cue.start_time, '.'),
+                    timestamp(cue.end_time, '.'),
+                    lines.join("<br/>")
+                );
+            }
+            out.push_str("    </div>\n  </body>\n</tt>\n");
+        }
+    }
+    out
+}
+
+/// Splits results into cues according to the options.
+///
+/// The first alternative of each result is used. Punctuation is joined onto the neighbouring words,
+/// and a cue always ends after punctuation marked with is_eos.
+pub fn subtitle_cues(results: &[RecognitionResult], options: &SubtitleOptions) -> Vec<SubtitleCue> {
+    let max_cue_duration = options.max_cue_duration.as_secs_f32();
+    let mut cues: Vec<SubtitleCue> = vec![];
+    let mut current: Option<SubtitleCue> = None;
+
+    for unit in join_units(results) {
+        let speaker = unit.speaker.filter(|_| options.speaker_labels);
+        let added = match current.as_mut() {
+            Some(cue)
+                if cue.speaker == speaker && unit.end_time - cue.start_time <= max_cue_duration =>
+            {
+                match add_to_lines(cue, &unit.text, options) {
+                    Some(lines) => {
+                        cue.lines = lines;
+                        cue.end_time = cue.end_time.max(unit.end_time);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        };
+
+        if !added {
+            cues.extend(current.take());
+            current = Some(SubtitleCue {
+                index: cues.len() as u32 + 1,
+                start_time: unit.start_time,
+                end_time: unit.end_time,
+                lines: vec![unit.text],
+                speaker,
+            });
+        }
+
+        if unit.ends_sentence {
+            cues.extend(current.take());
+        }
+    }
+    cues.extend(current);
+    cues
+}
+
+/// Returns the lines of the cue with the text added, either to the last line or a new one,
+/// or None if it doesn't fit within the max line length and max lines.
+fn add_to_lines(cue: &SubtitleCue, text: &str, options: &SubtitleOptions) -> Option<Vec<String>> {
+    let label_len = cue
+        .speaker
+        .as_ref()
+        .map_or(0, |speaker| speaker.chars().count() + 2);
+    let mut lines = cue.lines.clone();
+    let first_line = lines.len() == 1;
+    let last = lines.last_mut()?;
+    let last_len = last.chars().count() + if first_line { label_len } else { 0 };
+    if last_len + 1 + text.chars().count() <= options.max_line_length {
+        last.push(' ');
+        last.push_str(text);
+        return Some(lines);
+    }
+    if lines.len() < options.max_lines {
+        lines.push(text.to_owned());
+        return Some(lines);
+    }
+    None
+}
+
+/// A word together with the punctuation attached to it, which is never split across lines.
+struct Unit {
+    text: String,
+    start_time: f32,
+    end_time: f32,
+    speaker: Option<String>,
+    ends_sentence: bool,
+}
+
+fn join_units(results: &[RecognitionResult]) -> Vec<Unit> {
+    let mut units: Vec<Unit> = vec![];
+    let mut attach_next = false;
+    for result in results {
+        let Some(alternative) = result.alternatives.as_ref().and_then(|alts| alts.first()) else {
+            continue;
+        };
+        let attach =
+            Attachment::from_content(result.type_value == Type::Punctuation, &alternative.content);
+        match units.last_mut() {
+            // closing punctuation after the end of a sentence, like the ) in "(word.)", stays with the sentence
+            Some(unit) if attach.joins_previous() || (attach_next && !unit.ends_sentence) => {
+                unit.text.push_str(&alternative.content);
+                unit.end_time = unit.end_time.max(result.end_time);
+                unit.speaker = unit.speaker.take().or_else(|| alternative.speaker.clone());
+            }
+            _ => units.push(Unit {
+                text: alternative.content.clone(),
+                start_time: result.start_time,
+                end_time: result.end_time,
+                speaker: alternative
+                    .speaker
+                    .clone()
+                    .or_else(|| result.channel.clone()),
+                ends_sentence: false,
+            }),
+        }
+        if let Some(unit) = units.last_mut() {
+            unit.ends_sentence |= result.is_eos == Some(true);
+        }
+        attach_next = attach.joins_next();
+    }
+    units
+}
+
+/// Formats seconds as HH:MM:SS followed by the separator and milliseconds
+fn timestamp(seconds: f32, separator: char) -> String {
+    let millis = (seconds.max(0.0) as f64 * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        separator,
+        millis % 1000
+    )
+}
+
+/// Escapes the characters with a special meaning in WebVTT and XML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::models::RecognitionAlternative;
+
+    fn result(content: &str, start_time: f32, speaker: &str) -> RecognitionResult {
+        let is_punctuation = !content.chars().any(char::is_alphanumeric);
+        let mut alternative = RecognitionAlternative::new(content.to_owned(), 1.0, "en".to_owned());
+        alternative.speaker = Some(speaker.to_owned());
+        let mut result = RecognitionResult::new(
+            start_time,
+            start_time + if is_punctuation { 0.0 } else { 0.5 },
+            if is_punctuation {
+                Type::Punctuation
+            } else {
+                Type::Word
+            },
+        );
+        if content == "." {
+            result.is_eos = Some(true);
+        }
+        result.alternatives = Some(vec![alternative]);
+        result
+    }
+
+    #[test]
+    fn test_subtitle_cues() {
+        let results = vec![
+            result("Hello", 0.0, "S1"),
+            result(",", 0.5, "S1"),
+            result("my", 0.5, "S1"),
+            result("name", 1.0, "S1"),
+            result("is", 1.5, "S1"),
+            result("(", 2.0, "S1"),
+            result("Bob", 2.0, "S1"),
+            result(")", 2.5, "S1"),
+            result(".", 2.5, "S1"),
+            result("Hi", 3.0, "S2"),
+            result("Bob", 3.5, "S2"),
+            result("how", 4.0, "S1"),
+            result("are", 11.0, "S1"),
+            result("you", 11.5, "S1"),
+        ];
+        let options = SubtitleOptions {
+            max_line_length: 16,
+            ..Default::default()
+        };
+        let cues = subtitle_cues(&results, &options);
+        let lines: Vec<_> = cues.iter().map(|cue| cue.lines.clone()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                vec!["Hello, my", "name is (Bob)."],
+                vec!["Hi Bob"],
+                vec!["how"],
+                vec!["are you"],
+            ]
+        );
+        assert_eq!(cues[0].speaker.as_deref(), Some("S1"));
+        assert_eq!(cues[1].speaker.as_deref(), Some("S2"));
+        assert_eq!(
+            (cues[3].index, cues[3].start_time, cues[3].end_time),
+            (4, 11.0, 12.0)
+        );
+
+        let srt = render_cues(&cues[..2], SubtitleFormat::Srt, None);
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:02,500\nS1: Hello, my\nname is (Bob).\n\n2\n00:00:03,000 --> 00:00:04,000\nS2: Hi Bob\n\n"
+        );
+        let vtt = render_cues(&cues[1..2], SubtitleFormat::WebVtt, None);
+        assert_eq!(
+            vtt,
+            "WEBVTT\n\n2\n00:00:03.000 --> 00:00:04.000\n<v S2>Hi Bob\n\n"
+        );
+        let ttml = render_cues(&cues[..1], SubtitleFormat::Ttml, Some("en"));
+        assert!(ttml.contains("xml:lang=\"en\""));
+        assert!(ttml.contains(
+            "<p begin=\"00:00:00.000\" end=\"00:00:02.500\">S1: Hello, my<br/>name is (Bob).</p>"
+        ));
+
+        let options = SubtitleOptions {
+            speaker_labels: false,
+            max_lines: 1,
+            ..Default::default()
+        };
+        let cues = subtitle_cues(&results[..9], &options);
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].speaker, None);
+        assert_eq!(cues[0].lines, vec!["Hello, my name is (Bob)."]);
+    }
+
+    #[test]
+    fn test_punctuation_after_sentence_end() {
+        let results = vec![
+            result("Hi", 0.0, "S1"),
+            result("(", 0.5, "S1"),
+            result("Bob", 0.5, "S1"),
+            result(".", 1.0, "S1"),
+            result(")", 1.0, "S1"),
+            result("Bye", 1.5, "S1"),
+        ];
+        let cues = subtitle_cues(&results, &SubtitleOptions::default());
+        let lines: Vec<_> = cues.iter().map(|cue| cue.lines.clone()).collect();
+        assert_eq!(lines, vec![vec!["Hi (Bob.)"], vec!["Bye"]]);
+    }
+}
@@ -0,0 +1,165 @@
+//! Types for the formats a transcript can be retrieved in, and a parser for SRT subtitles.
+
+use super::models::RetrieveTranscriptResponse;
+use crate::error::{Error, Result};
+
+/// The formats a transcript can be retrieved in from the batch API.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum TranscriptFormat {
+    /// The full JSON output, including metadata, translations and summaries. This is the default.
+    #[default]
+    JsonV2,
+    /// The plain text of the transcript.
+    Txt,
+    /// SubRip subtitles, wrapped according to the srt_overrides in the output_config of the job.
+    Srt,
+}
+
+impl std::fmt::Display for TranscriptFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::JsonV2 => write!(f, "json-v2"),
+            Self::Txt => write!(f, "txt"),
+            Self::Srt => write!(f, "srt"),
+        }
+    }
+}
+
+/// A transcript retrieved from the batch API, in the format it was requested in.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Transcript {
+    /// The transcript in json-v2 format.
+    JsonV2(Box<RetrieveTranscriptResponse>),
+    /// The transcript in txt format.
+    Txt(String),
+    /// The transcript in srt format.
+    Srt(SrtTranscript),
+}
+
+/// An SRT transcript, as both the raw text returned by the server and the cues parsed from it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SrtTranscript {
+    /// The SRT file exactly as it was returned by the server.
+    pub raw: String,
+    /// The cues of the SRT file, in order.
+    pub cues: Vec<SubtitleCue>,
+}
+
+/// A single subtitle, shown on screen between its start and end times (in seconds).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SubtitleCue {
+    /// The number of the cue, which starts at 1.
+    pub index: u32,
+    /// The time the cue is shown at in seconds.
+    pub start_time: f32,
+    /// The time the cue is hidden at in seconds.
+    pub end_time: f32,
+    /// The lines of text in the cue. These are kept as they were split by the server, so they respect
+    /// the max_line_length and max_lines set in the srt_overrides of the job.
+    pub lines: Vec<String>,
+    /// The speaker (or channel) the cue is labelled with. This is only set for cues rendered locally, as the
+    /// labels in an SRT file from the server are part of its text.
+    pub speaker: Option<String>,
+}
+
+impl SubtitleCue {
+    /// Returns the text of the cue with its lines joined by spaces.
+    pub fn text(&self) -> String {
+        self.lines.join(" ")
+    }
+}
+
+/// Parses an SRT file into a list of cues.
+///
+/// # Errors
+///
+/// This function will return Error::Decode if a cue is missing its number or timing line, or a timestamp is not in HH:MM:SS,mmm format.
+pub fn parse_srt(text: &str) -> Result<Vec<SubtitleCue>> {
+    let text = text.trim_start_matches('\u{feff}');
+    let mut cues = vec![];
+    let mut lines = text
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .peekable();
+    loop {
+        while lines.next_if(|line| line.trim().is_empty()).is_some() {}
+        let Some(index_line) = lines.next() else {
+            break;
+        };
+        let index = index_line
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| decode_error(format!("invalid cue number {}", index_line)))?;
+        let timing = lines
+            .next()
+            .ok_or_else(|| decode_error(format!("cue {} has no timing line", index)))?;
+        let (start, end) = timing
+            .split_once("-->")
+            .ok_or_else(|| decode_error(format!("invalid timing line {}", timing)))?;
+
+        let mut cue_lines = vec![];
+        while let Some(line) = lines.next_if(|line| !line.trim().is_empty()) {
+            cue_lines.push(line.to_owned());
+        }
+
+        cues.push(SubtitleCue {
+            index,
+            start_time: parse_timestamp(start.trim())?,
+            end_time: parse_timestamp(end.trim())?,
+            lines: cue_lines,
+            speaker: None,
+        });
+    }
+    Ok(cues)
+}
+
+/// Parses a HH:MM:SS,mmm timestamp into seconds
+fn parse_timestamp(timestamp: &str) -> Result<f32> {
+    let invalid = || decode_error(format!("invalid timestamp {} in srt", timestamp));
+    let (time, millis) = timestamp.split_once(',').ok_or_else(invalid)?;
+    let mut seconds = 0.0;
+    for part in time.split(':') {
+        seconds = seconds * 60.0 + part.parse::<u32>().map_err(|_| invalid())? as f64;
+    }
+    let millis = millis.parse::<u32>().map_err(|_| invalid())?;
+    Ok((seconds + millis as f64 / 1000.0) as f32)
+}
+
+fn decode_error(reason: String) -> Error {
+    Error::Decode(reason.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_srt() {
+        let text = "\u{feff}1\r\n00:00:00,480 --> 00:00:02,100\r\nHello world,\r\nthis is a test.\r\n\r\n2\r\n01:02:03,040 --> 01:02:04,500\r\nSecond cue.\r\n";
+        let cues = parse_srt(text).unwrap();
+        assert_eq!(
+            cues,
+            vec![
+                SubtitleCue {
+                    index: 1,
+                    start_time: 0.48,
+                    end_time: 2.1,
+                    lines: vec!["Hello world,".to_owned(), "this is a test.".to_owned()],
+                    speaker: None,
+                },
+                SubtitleCue {
+                    index: 2,
+                    start_time: 3723.04,
+                    end_time: 3724.5,
+                    lines: vec!["Second cue.".to_owned()],
+                    speaker: None,
+                },
+            ]
+        );
+        assert_eq!(cues[0].text(), "Hello world, this is a test.");
+
+        assert!(parse_srt("").unwrap().is_empty());
+        assert!(parse_srt("1\n00:00:00.480 --> 00:00:02,100\nhello\n").is_err());
+        assert!(parse_srt("one\n00:00:00,480 --> 00:00:02,100\nhello\n").is_err());
+    }
+}
@@ -0,0 +1,243 @@
+//! Types for streaming the files of a batch job into the request body, rather than reading them into memory first.
+
+use bytes::Bytes;
+use futures::{Stream, TryStream};
+use futures_io::AsyncRead;
+use reqwest::{multipart::Part, Body};
+use std::{
+    fs::File,
+    path::PathBuf,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A file to upload as part of a job, i.e. the data_file containing the audio or the text_file of an alignment job.
+///
+/// It can be created from a path, an in-memory buffer, an AsyncRead or a Stream of bytes. In every case, the data is
+/// streamed into the request body as it is sent, so large files are never held in memory.
+/// A PathBuf can be passed anywhere a JobFile is expected.
+///
+/// If the content length is known, it should be provided so that the upload doesn't need to use chunked encoding.
+///
+/// # Example
+///
+/// ```
+/// use speechmatics::batch::upload::JobFile;
+///
+/// let in_memory = JobFile::from_bytes("example.wav", vec![0u8; 1024]);
+/// let reader = JobFile::from_reader("example.wav", futures::io::Cursor::new(vec![0u8; 1024]), Some(1024));
+/// ```
+pub struct JobFile {
+    source: Source,
+    file_name: String,
+    content_length: Option<u64>,
+}
+
+enum Source {
+    Path(PathBuf),
+    Body(Body),
+}
+
+impl JobFile {
+    /// Creates a JobFile which streams the file at the given path. The file is opened when the job is submitted.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let mut file_name: String = "".to_owned();
+        if let Some(fil_name) = path.file_name() {
+            if let Some(string_file) = fil_name.to_str() {
+                file_name = string_file.to_owned();
+            }
+        }
+        Self {
+            source: Source::Path(path),
+            file_name,
+            content_length: None,
+        }
+    }
+
+    /// Creates a JobFile from a buffer that is already in memory.
+    pub fn from_bytes(file_name: impl Into<String>, bytes: impl Into<Bytes>) -> Self {
+        let bytes: Bytes = bytes.into();
+        let content_length = Some(bytes.len() as u64);
+        Self {
+            source: Source::Body(Body::from(bytes)),
+            file_name: file_name.into(),
+            content_length,
+        }
+    }
+
+    /// Creates a JobFile from anything that implements futures' AsyncRead.
+    ///
+    /// Tokio readers, such as a tokio File, can be passed to from_tokio_reader instead.
+    pub fn from_reader<R>(
+        file_name: impl Into<String>,
+        reader: R,
+        content_length: Option<u64>,
+    ) -> Self
+    where
+        R: AsyncRead + Send + Sync + Unpin + 'static,
+    {
+        Self::from_stream(file_name, ReaderStream::new(reader), content_length)
+    }
+
+    /// Creates a JobFile from anything that implements tokio's AsyncRead, e.g. a tokio File. Requires the tokio feature.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run() {
+    /// use speechmatics::batch::upload::JobFile;
+    ///
+    /// let file = tokio::fs::File::open("example.wav").await.unwrap();
+    /// let length = file.metadata().await.unwrap().len();
+    /// let job_file = JobFile::from_tokio_reader("example.wav", file, Some(length));
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn from_tokio_reader<R>(
+        file_name: impl Into<String>,
+        reader: R,
+        content_length: Option<u64>,
+    ) -> Self
+    where
+        R: tokio::io::AsyncRead + Send + Sync + Unpin + 'static,
+    {
+        Self::from_reader(file_name, TokioReader(reader), content_length)
+    }
+
+    /// Creates a JobFile from a stream of chunks of bytes, e.g. the body of an HTTP response.
+    pub fn from_stream<S>(
+        file_name: impl Into<String>,
+        stream: S,
+        content_length: Option<u64>,
+    ) -> Self
+    where
+        S: TryStream + Send + Sync + 'static,
+        S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+        Bytes: From<S::Ok>,
+    {
+        Self {
+            source: Source::Body(Body::wrap_stream(stream)),
+            file_name: file_name.into(),
+            content_length,
+        }
+    }
+
+    /// The name the file will be uploaded with. This is used as the data_name (or text_name) of the job.
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    /// Converts the file into a multipart part, opening it first if it is a path.
+    /// The file is opened on a blocking thread pool, so that the executor isn't blocked.
+    pub(crate) async fn into_part(self) -> std::io::Result<Part> {
+        let (body, content_length) = match self.source {
+            Source::Path(path) => {
+                let (file, length) = blocking::unblock(move || {
+                    let file = File::open(path)?;
+                    let length = file.metadata()?.len();
+                    Ok::<_, std::io::Error>((file, length))
+                })
+                .await?;
+                let reader = blocking::Unblock::with_capacity(CHUNK_SIZE, file);
+                (Body::wrap_stream(ReaderStream::new(reader)), Some(length))
+            }
+            Source::Body(body) => (body, self.content_length),
+        };
+        let part = match content_length {
+            Some(length) => Part::stream_with_length(body, length),
+            None => Part::stream(body),
+        };
+        Ok(part.file_name(self.file_name))
+    }
+}
+
+impl From<PathBuf> for JobFile {
+    fn from(path: PathBuf) -> Self {
+        Self::from_path(path)
+    }
+}
+
+impl From<&std::path::Path> for JobFile {
+    fn from(path: &std::path::Path) -> Self {
+        Self::from_path(path)
+    }
+}
+
+/// Adapts an AsyncRead into a Stream of chunks of bytes.
+struct ReaderStream<R> {
+    reader: R,
+    buffer: Vec<u8>,
+}
+
+impl<R> ReaderStream<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: vec![0u8; CHUNK_SIZE],
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for ReaderStream<R> {
+    type Item = std::io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        match Pin::new(&mut this.reader).poll_read(cx, &mut this.buffer) {
+            Poll::Ready(Ok(0)) => Poll::Ready(None),
+            Poll::Ready(Ok(no)) => {
+                Poll::Ready(Some(Ok(Bytes::copy_from_slice(&this.buffer[..no]))))
+            }
+            Poll::Ready(Err(err)) => Poll::Ready(Some(Err(err))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Adapts a tokio AsyncRead into futures' AsyncRead.
+#[cfg(feature = "tokio")]
+struct TokioReader<R>(R);
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + Unpin> AsyncRead for TokioReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let mut read_buf = tokio::io::ReadBuf::new(buf);
+        Pin::new(&mut self.0)
+            .poll_read(cx, &mut read_buf)
+            .map_ok(|()| read_buf.filled().len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{io::Cursor, TryStreamExt};
+
+    #[tokio::test]
+    async fn test_reader_stream() {
+        let data: Vec<u8> = (0..CHUNK_SIZE * 2 + 10).map(|i| i as u8).collect();
+        let chunks: Vec<Bytes> = ReaderStream::new(Cursor::new(data.clone()))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), data);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_tokio_reader_stream() {
+        let data: Vec<u8> = (0..CHUNK_SIZE + 10).map(|i| i as u8).collect();
+        let reader = TokioReader(std::io::Cursor::new(data.clone()));
+        let chunks: Vec<Bytes> = ReaderStream::new(reader).try_collect().await.unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks.concat(), data);
+    }
+}
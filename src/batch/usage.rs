@@ -0,0 +1,157 @@
+//! Helpers for summarising the usage statistics returned by BatchClient::get_usage.
+
+use super::models::{JobType, OperatingPoint, UsageResponse};
+use std::collections::BTreeMap;
+
+/// Sets which properties usage is grouped by in aggregate_usage.
+/// Properties which are not grouped by are left as None in the UsageKey.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UsageGroupBy {
+    /// Group usage by the type of job, i.e. transcription or alignment.
+    pub job_type: bool,
+    /// Group usage by the operating point, i.e. standard or enhanced.
+    pub operating_point: bool,
+    /// Group usage by language.
+    pub language: bool,
+}
+
+/// The key that aggregated usage is grouped under.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UsageKey {
+    /// The type of job, if usage is grouped by job type.
+    pub job_type: Option<JobType>,
+    /// The operating point, if usage is grouped by operating point and the usage had one.
+    pub operating_point: Option<OperatingPoint>,
+    /// The language, if usage is grouped by language and the usage had one.
+    pub language: Option<String>,
+}
+
+/// The total usage for a UsageKey.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct UsageTotal {
+    /// Total number of billable jobs.
+    pub count: i64,
+    /// Total duration of billable jobs in hours.
+    pub duration_hrs: f64,
+}
+
+/// Aggregates the detailed usage in a UsageResponse, summing the job count and duration for each group.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn run() {
+/// use speechmatics::batch::{
+///     usage::{aggregate_usage, UsageGroupBy},
+///     BatchClient,
+/// };
+///
+/// let batch_client = BatchClient::new("API_KEY", None).unwrap();
+/// let usage = batch_client.get_usage(None, None).await.unwrap();
+///
+/// let group_by = UsageGroupBy {
+///     operating_point: true,
+///     ..Default::default()
+/// };
+/// for (key, total) in aggregate_usage(&usage, group_by) {
+///     println!("{:?}: {} hours", key.operating_point, total.duration_hrs);
+/// }
+/// # }
+/// ```
+pub fn aggregate_usage(
+    usage: &UsageResponse,
+    group_by: UsageGroupBy,
+) -> BTreeMap<UsageKey, UsageTotal> {
+    let mut totals: BTreeMap<UsageKey, UsageTotal> = BTreeMap::new();
+    for details in usage.details.iter() {
+        let key = UsageKey {
+            job_type: group_by.job_type.then_some(details.type_value),
+            operating_point: details.operating_point.filter(|_| group_by.operating_point),
+            language: details.language.clone().filter(|_| group_by.language),
+        };
+        let total = totals.entry(key).or_default();
+        total.count += i64::from(details.count);
+        total.duration_hrs += f64::from(details.duration_hrs);
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::models::{JobMode, UsageDetails};
+
+    fn details(
+        type_value: JobType,
+        language: &str,
+        operating_point: OperatingPoint,
+        count: i32,
+        duration_hrs: f32,
+    ) -> UsageDetails {
+        let mut details = UsageDetails::new(JobMode::Batch, type_value, count, duration_hrs);
+        details.language = Some(language.to_owned());
+        details.operating_point = Some(operating_point);
+        details
+    }
+
+    #[test]
+    fn test_aggregate_usage() {
+        let usage = UsageResponse::new(
+            "2023-09-01".to_owned(),
+            "2023-09-30".to_owned(),
+            vec![],
+            vec![
+                details(
+                    JobType::Transcription,
+                    "en",
+                    OperatingPoint::Standard,
+                    4,
+                    1.5,
+                ),
+                details(
+                    JobType::Transcription,
+                    "de",
+                    OperatingPoint::Enhanced,
+                    1,
+                    0.25,
+                ),
+                details(JobType::Alignment, "en", OperatingPoint::Standard, 2, 0.5),
+            ],
+        );
+
+        let by_operating_point = aggregate_usage(
+            &usage,
+            UsageGroupBy {
+                operating_point: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(by_operating_point.len(), 2);
+        let standard = UsageKey {
+            job_type: None,
+            operating_point: Some(OperatingPoint::Standard),
+            language: None,
+        };
+        assert_eq!(
+            by_operating_point[&standard],
+            UsageTotal {
+                count: 6,
+                duration_hrs: 2.0
+            }
+        );
+
+        let everything = aggregate_usage(&usage, UsageGroupBy::default());
+        assert_eq!(everything.len(), 1);
+        assert_eq!(everything.values().next().unwrap().count, 7);
+
+        let by_all = aggregate_usage(
+            &usage,
+            UsageGroupBy {
+                job_type: true,
+                operating_point: true,
+                language: true,
+            },
+        );
+        assert_eq!(by_all.len(), 3);
+    }
+}
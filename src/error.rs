@@ -0,0 +1,361 @@
+//! The error type shared by the batch and realtime clients.
+//!
+//! Every fallible function in this crate returns an [`Error`], which can be matched on to decide how to handle a failure,
+//! for example retrying on a transport error but surfacing an auth error to the user.
+
+use std::fmt;
+
+/// A Result type alias where the error defaults to the speechmatics [`Error`].
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Enum of all the ways a request to the Speechmatics API can fail.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Something went wrong talking to the API, e.g. the connection could not be made or was dropped.
+    Transport(TransportError),
+    /// The API rejected the credentials, e.g. an invalid or expired API key.
+    Auth(ApiError),
+    /// An AuthProvider could not get a token, e.g. because the token endpoint was unreachable or refused to issue a key.
+    /// This is raised before the API itself is called, and holds the error from getting the token.
+    #[cfg(any(feature = "batch", feature = "realtime"))]
+    TokenRefresh(Box<Error>),
+    /// The account does not have enough credit to complete the request.
+    Quota(ApiError),
+    /// The API returned an error which is not covered by one of the more specific variants.
+    Api(ApiError),
+    /// The server did not follow the expected message flow, e.g. closing the connection before the session finished.
+    Protocol(String),
+    /// Reading from the audio source failed.
+    AudioRead(std::io::Error),
+    /// A request could not be serialised or a response could not be deserialised.
+    Decode(Box<dyn std::error::Error + Send + Sync>),
+    /// An argument passed to the client was invalid, e.g. a malformed URL or header value.
+    InvalidInput(String),
+    /// The channel used to pass messages back to the caller was closed whilst the session was still running.
+    ChannelClosed,
+    /// The channel used to pass messages back to the caller was full, and its overflow policy is to fail.
+    ChannelFull,
+    /// The operation did not complete within its configured timeout.
+    Timeout,
+    /// The operation was cancelled by the caller before it completed.
+    Cancelled,
+    /// A batch job finished without succeeding, i.e. it was rejected, deleted or expired.
+    /// The job details include the final status and any errors reported for the job.
+    #[cfg(feature = "batch")]
+    JobFailed(Box<crate::batch::models::JobDetails>),
+}
+
+/// The underlying transport failure, which depends on the client in use.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TransportError {
+    /// An HTTP error from the batch client, or from fetching a temporary key.
+    #[cfg(any(feature = "batch", feature = "realtime"))]
+    Http(reqwest::Error),
+    /// A websocket error from the realtime client. This is boxed as the websocket error type is large.
+    #[cfg(feature = "realtime")]
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+}
+
+/// An error reported by the API itself, parsed from the server response.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ApiError {
+    /// An Error message sent by the realtime server. This includes the type, reason, code and seq_no of the error.
+    #[cfg(feature = "realtime")]
+    Realtime(crate::realtime::models::Error),
+    /// The body of an unsuccessful response from the batch API. This includes the HTTP status code, the error and its detail.
+    #[cfg(feature = "batch")]
+    Batch(crate::batch::models::ErrorResponse),
+    /// An unsuccessful response from the batch API whose body could not be parsed into an ErrorResponse,
+    /// e.g. a rate limited request or an error message which this version of the crate doesn't know about.
+    /// This is also used for unsuccessful responses when fetching a temporary key.
+    #[cfg(any(feature = "batch", feature = "realtime"))]
+    Http {
+        /// The HTTP status code of the response.
+        status: u16,
+        /// The raw body of the response.
+        body: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Transport(err) => write!(f, "transport error: {}", err),
+            Error::Auth(err) => write!(f, "authentication failed: {}", err),
+            #[cfg(any(feature = "batch", feature = "realtime"))]
+            Error::TokenRefresh(err) => write!(f, "failed to get an auth token: {}", err),
+            Error::Quota(err) => write!(f, "insufficient funds: {}", err),
+            Error::Api(err) => write!(f, "received error from server: {}", err),
+            Error::Protocol(reason) => write!(f, "protocol error: {}", reason),
+            Error::AudioRead(err) => write!(f, "failed to read audio: {}", err),
+            Error::Decode(err) => write!(f, "failed to decode message: {}", err),
+            Error::InvalidInput(reason) => write!(f, "invalid input: {}", reason),
+            Error::ChannelClosed => write!(f, "message channel was closed"),
+            Error::ChannelFull => write!(f, "message channel was full"),
+            Error::Timeout => write!(f, "operation timed out"),
+            Error::Cancelled => write!(f, "operation was cancelled"),
+            #[cfg(feature = "batch")]
+            Error::JobFailed(job) => {
+                write!(f, "job {} finished with status {:?}", job.id, job.status)?;
+                for err in job.errors.iter().flatten() {
+                    write!(f, ", {}: {}", err.timestamp, err.message)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Transport(err) => Some(err),
+            Error::AudioRead(err) => Some(err),
+            Error::Decode(err) => Some(err.as_ref()),
+            #[cfg(any(feature = "batch", feature = "realtime"))]
+            Error::TokenRefresh(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TransportError {
+    // without the batch or realtime features there are no variants, so nothing is written
+    #[cfg_attr(
+        not(any(feature = "batch", feature = "realtime")),
+        allow(unused_variables)
+    )]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            #[cfg(any(feature = "batch", feature = "realtime"))]
+            TransportError::Http(ref err) => err.fmt(f),
+            #[cfg(feature = "realtime")]
+            TransportError::WebSocket(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            #[cfg(any(feature = "batch", feature = "realtime"))]
+            TransportError::Http(ref err) => Some(err),
+            #[cfg(feature = "realtime")]
+            TransportError::WebSocket(ref err) => Some(err.as_ref()),
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    // without the batch or realtime features there are no variants, so nothing is written
+    #[cfg_attr(
+        not(any(feature = "batch", feature = "realtime")),
+        allow(unused_variables)
+    )]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            #[cfg(feature = "realtime")]
+            ApiError::Realtime(ref err) => write!(f, "{:?}: {}", err.type_value, err.reason),
+            #[cfg(feature = "batch")]
+            ApiError::Batch(ref err) => match &err.detail {
+                Some(detail) => write!(f, "{} {:?}: {}", err.code, err.error, detail),
+                None => write!(f, "{} {:?}", err.code, err.error),
+            },
+            #[cfg(any(feature = "batch", feature = "realtime"))]
+            ApiError::Http { status, ref body } => write!(f, "{} {}", status, body),
+        }
+    }
+}
+
+impl Error {
+    /// Returns the error reported by the API, if there was one.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Error::Auth(err) | Error::Quota(err) | Error::Api(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns the HTTP status code of a failed batch request, if the server responded at all.
+    #[cfg(feature = "batch")]
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Transport(TransportError::Http(err)) => {
+                err.status().map(|status| status.as_u16())
+            }
+            _ => match self.api_error()? {
+                ApiError::Batch(err) => u16::try_from(err.code).ok(),
+                ApiError::Http { status, .. } => Some(*status),
+                #[allow(unreachable_patterns)]
+                _ => None,
+            },
+        }
+    }
+
+    /// Returns the parsed ErrorResponse of a failed batch request, if the server sent one.
+    ///
+    /// The error property can be used to tell failures apart, e.g. a transcript that isn't ready yet
+    /// from a job that was rejected because of invalid audio. The detail property gives a human readable explanation.
+    #[cfg(feature = "batch")]
+    pub fn error_response(&self) -> Option<&crate::batch::models::ErrorResponse> {
+        match self.api_error()? {
+            ApiError::Batch(err) => Some(err),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Decode(Box::new(err))
+    }
+}
+
+impl From<std::string::FromUtf8Error> for Error {
+    fn from(err: std::string::FromUtf8Error) -> Self {
+        Error::Decode(Box::new(err))
+    }
+}
+
+impl From<url::ParseError> for Error {
+    fn from(err: url::ParseError) -> Self {
+        Error::InvalidInput(err.to_string())
+    }
+}
+
+#[cfg(any(feature = "batch", feature = "realtime"))]
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            return Error::Timeout;
+        }
+        Error::Transport(TransportError::Http(err))
+    }
+}
+
+#[cfg(any(feature = "batch", feature = "realtime"))]
+impl From<reqwest::header::InvalidHeaderValue> for Error {
+    fn from(err: reqwest::header::InvalidHeaderValue) -> Self {
+        Error::InvalidInput(err.to_string())
+    }
+}
+
+#[cfg(feature = "realtime")]
+impl From<tokio_tungstenite::tungstenite::Error> for Error {
+    fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
+        Error::Transport(TransportError::WebSocket(Box::new(err)))
+    }
+}
+
+#[cfg(feature = "realtime")]
+impl From<http::Error> for Error {
+    fn from(err: http::Error) -> Self {
+        Error::InvalidInput(err.to_string())
+    }
+}
+
+#[cfg(feature = "realtime")]
+impl<T> From<tokio::sync::mpsc::error::SendError<T>> for Error {
+    fn from(_: tokio::sync::mpsc::error::SendError<T>) -> Self {
+        Error::ChannelClosed
+    }
+}
+
+#[cfg(feature = "realtime")]
+impl From<crate::realtime::models::Error> for Error {
+    fn from(err: crate::realtime::models::Error) -> Self {
+        use crate::realtime::models::error::Type;
+        match err.type_value {
+            Type::NotAuthorised => Error::Auth(ApiError::Realtime(err)),
+            Type::InsufficientFunds => Error::Quota(ApiError::Realtime(err)),
+            _ => Error::Api(ApiError::Realtime(err)),
+        }
+    }
+}
+
+#[cfg(feature = "batch")]
+impl From<crate::batch::models::ErrorResponse> for Error {
+    fn from(err: crate::batch::models::ErrorResponse) -> Self {
+        use crate::batch::models::error_response::Error as Kind;
+        match (err.code, err.error) {
+            (401, _) | (_, Kind::PermissionDenied) => Error::Auth(ApiError::Batch(err)),
+            (402, _) => Error::Quota(ApiError::Batch(err)),
+            _ => Error::Api(ApiError::Batch(err)),
+        }
+    }
+}
+
+#[cfg(feature = "batch")]
+impl Error {
+    /// Builds an error from an unsuccessful batch response, classifying it by its body if possible and otherwise by its status.
+    pub(crate) fn from_batch_response(status: u16, body: &[u8]) -> Self {
+        match serde_json::from_slice::<crate::batch::models::ErrorResponse>(body) {
+            Ok(error_response) => Error::from(error_response),
+            Err(_) => {
+                let err = ApiError::Http {
+                    status,
+                    body: String::from_utf8_lossy(body).into_owned(),
+                };
+                match status {
+                    401 => Error::Auth(err),
+                    402 => Error::Quota(err),
+                    _ => Error::Api(err),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "realtime")]
+    #[test]
+    fn test_realtime_error_classification() {
+        use crate::realtime::models::error::{Message, Type};
+        use crate::realtime::models::Error as ServerError;
+
+        let auth = ServerError::new(Message::Error, "bad key".to_owned(), Type::NotAuthorised);
+        assert!(matches!(
+            Error::from(auth),
+            Error::Auth(ApiError::Realtime(_))
+        ));
+
+        let quota = ServerError::new(
+            Message::Error,
+            "no credit".to_owned(),
+            Type::InsufficientFunds,
+        );
+        assert!(matches!(Error::from(quota), Error::Quota(_)));
+
+        let mut buffer = ServerError::new(Message::Error, "too fast".to_owned(), Type::BufferError);
+        buffer.seq_no = Some(12);
+        match Error::from(buffer) {
+            Error::Api(ApiError::Realtime(err)) => assert_eq!(err.seq_no, Some(12)),
+            err => panic!("unexpected error {:?}", err),
+        }
+    }
+
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_batch_error_classification() {
+        use crate::batch::models::error_response::Error as Kind;
+        use crate::batch::models::ErrorResponse;
+
+        let auth = ErrorResponse::new(401, Kind::PermissionDenied);
+        assert!(matches!(Error::from(auth), Error::Auth(ApiError::Batch(_))));
+
+        let mut not_ready = ErrorResponse::new(404, Kind::TranscriptionNotReady);
+        not_ready.detail = Some("job is still running".to_owned());
+        let err = Error::from(not_ready);
+        assert_eq!(
+            err.to_string(),
+            "received error from server: 404 TranscriptionNotReady: job is still running"
+        );
+    }
+}
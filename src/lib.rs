@@ -2,10 +2,19 @@
 
 #![warn(missing_docs)]
 
-#[macro_use]
+#[cfg_attr(any(feature = "batch", feature = "realtime"), macro_use)]
 extern crate serde;
 
+#[cfg(any(feature = "batch", feature = "realtime"))]
+pub mod auth;
 #[cfg(feature = "batch")]
 pub mod batch;
+pub mod error;
+#[cfg(any(feature = "batch", feature = "realtime"))]
+mod punctuation;
 #[cfg(feature = "realtime")]
 pub mod realtime;
+#[cfg(any(feature = "batch", feature = "realtime"))]
+pub mod turns;
+
+pub use error::{Error, Result};
@@ -0,0 +1,60 @@
+//! How punctuation joins onto the words around it. Subtitles, speaker turns and the realtime transcript builder all
+//! use this, so that they join text in the same way.
+
+/// How a word or punctuation mark joins onto its neighbours.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Attachment {
+    None,
+    Previous,
+    Next,
+    Both,
+}
+
+impl Attachment {
+    /// Works out how an item joins onto its neighbours when the API didn't send attaches_to, which the batch API
+    /// never does. Words attach to neither neighbour, and punctuation marks match the behaviour of the realtime API.
+    pub(crate) fn from_content(is_punctuation: bool, content: &str) -> Self {
+        if !is_punctuation {
+            return Attachment::None;
+        }
+        match content {
+            "(" | "[" | "{" | "¿" | "¡" | "«" | "“" | "‘" => Attachment::Next,
+            "-" | "/" => Attachment::Both,
+            _ => Attachment::Previous,
+        }
+    }
+
+    pub(crate) fn joins_previous(self) -> bool {
+        matches!(self, Attachment::Previous | Attachment::Both)
+    }
+
+    pub(crate) fn joins_next(self) -> bool {
+        matches!(self, Attachment::Next | Attachment::Both)
+    }
+}
+
+#[cfg(feature = "realtime")]
+impl From<crate::realtime::models::recognition_result::AttachesTo> for Attachment {
+    fn from(attaches_to: crate::realtime::models::recognition_result::AttachesTo) -> Self {
+        use crate::realtime::models::recognition_result::AttachesTo;
+        match attaches_to {
+            AttachesTo::None => Attachment::None,
+            AttachesTo::Previous => Attachment::Previous,
+            AttachesTo::Next => Attachment::Next,
+            AttachesTo::Both => Attachment::Both,
+        }
+    }
+}
+
+#[cfg(feature = "realtime")]
+impl From<Attachment> for crate::realtime::models::recognition_result::AttachesTo {
+    fn from(attachment: Attachment) -> Self {
+        use crate::realtime::models::recognition_result::AttachesTo;
+        match attachment {
+            Attachment::None => AttachesTo::None,
+            Attachment::Previous => AttachesTo::Previous,
+            Attachment::Next => AttachesTo::Next,
+            Attachment::Both => AttachesTo::Both,
+        }
+    }
+}
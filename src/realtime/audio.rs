@@ -0,0 +1,120 @@
+//! A channel for pushing audio into a realtime session, for audio that arrives in frames rather than from a reader.
+
+use crate::error::{Error, Result};
+use bytes::Bytes;
+use futures::Stream;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+/// Creates a channel for pushing audio into a session. The AudioStream is passed to RealtimeSession::run_from_stream,
+/// and each frame sent through the AudioSender is sent to the server as a chunk of audio.
+///
+/// Up to buffer frames can be waiting to be sent before send waits for space. Values below 1 are treated as 1.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn run() {
+/// use bytes::Bytes;
+/// use speechmatics::realtime::{audio::audio_channel, RealtimeSession, SessionConfig};
+///
+/// let (mut rt_session, _) = RealtimeSession::new("YOUR_API_KEY".to_owned(), None).unwrap();
+/// let (audio_sender, audio_stream) = audio_channel(32);
+///
+/// tokio::spawn(async move {
+///     for _ in 0..10 {
+///         audio_sender.send(Bytes::from(vec![0u8; 3200])).await.unwrap();
+///     }
+///     audio_sender.finish();
+/// });
+/// rt_session
+///     .run_from_stream(SessionConfig::default(), audio_stream)
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+///
+pub fn audio_channel(buffer: usize) -> (AudioSender, AudioStream) {
+    let (sender, receiver) = mpsc::channel(buffer.max(1));
+    (AudioSender { sender }, AudioStream { receiver })
+}
+
+/// The sending half of an audio channel, see audio_channel.
+#[derive(Debug)]
+pub struct AudioSender {
+    sender: mpsc::Sender<Bytes>,
+}
+
+impl AudioSender {
+    /// Sends a frame of audio, waiting for space if the channel is full. Empty frames are ignored.
+    ///
+    /// # Errors
+    ///
+    /// This function will return Error::ChannelClosed if the session has stopped reading audio, e.g. because it failed.
+    pub async fn send(&self, audio: Bytes) -> Result<()> {
+        if audio.is_empty() {
+            return Ok(());
+        }
+        self.sender.send(audio).await?;
+        Ok(())
+    }
+
+    /// Sends a frame of audio without waiting, e.g. from a synchronous callback. Empty frames are ignored.
+    ///
+    /// # Errors
+    ///
+    /// This function will return Error::ChannelFull if the channel is full, in which case the frame is dropped,
+    /// or Error::ChannelClosed if the session has stopped reading audio.
+    pub fn try_send(&self, audio: Bytes) -> Result<()> {
+        if audio.is_empty() {
+            return Ok(());
+        }
+        self.sender.try_send(audio).map_err(|err| match err {
+            TrySendError::Full(_) => Error::ChannelFull,
+            TrySendError::Closed(_) => Error::ChannelClosed,
+        })
+    }
+
+    /// Marks the end of the audio. Once the frames already sent have been passed on, the session sends EndOfStream
+    /// and waits for the server to finish transcribing. Dropping the sender has the same effect.
+    pub fn finish(self) {}
+}
+
+/// The receiving half of an audio channel, see audio_channel.
+#[derive(Debug)]
+pub struct AudioStream {
+    receiver: mpsc::Receiver<Bytes>,
+}
+
+impl Stream for AudioStream {
+    type Item = Bytes;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_audio_channel() {
+        let (sender, mut stream) = audio_channel(1);
+        sender.send(Bytes::from_static(b"one")).await.unwrap();
+        sender.send(Bytes::new()).await.unwrap();
+        assert!(matches!(
+            sender.try_send(Bytes::from_static(b"two")),
+            Err(Error::ChannelFull)
+        ));
+        assert_eq!(stream.next().await.unwrap(), Bytes::from_static(b"one"));
+        sender.try_send(Bytes::from_static(b"two")).unwrap();
+        sender.finish();
+        assert_eq!(stream.next().await.unwrap(), Bytes::from_static(b"two"));
+        assert_eq!(stream.next().await, None);
+    }
+}
@@ -0,0 +1,178 @@
+//! An AsyncRead adapter which releases audio at the speed it would be captured, for replaying recordings into a session.
+
+use super::models::{audio_format, AudioFormat};
+use crate::error::{Error, Result};
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, ReadBuf},
+    time::{sleep_until, Instant, Sleep},
+};
+
+/// PacedReader wraps an audio source and throttles it, so that each chunk is only released once the audio before it
+/// would have finished playing. This makes a file behave like a live stream, e.g. to test latency.
+///
+/// The speed multiplier scales the pace, so 2.0 sends audio twice as fast as it would be captured.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn run() {
+/// use speechmatics::realtime::{models, pacing::PacedReader, RealtimeSession, SessionConfig};
+/// use tokio::fs::File;
+///
+/// let (mut rt_session, _) = RealtimeSession::new("YOUR_API_KEY".to_owned(), None).unwrap();
+///
+/// let mut audio_format = models::AudioFormat::new(models::audio_format::Type::Raw);
+/// audio_format.encoding = Some(models::audio_format::Encoding::PcmS16le);
+/// audio_format.sample_rate = Some(16000);
+///
+/// let file = File::open("example.raw").await.unwrap();
+/// let reader = PacedReader::from_audio_format(file, &audio_format, None).unwrap();
+///
+/// let mut config = SessionConfig::default();
+/// config.audio_format = Some(audio_format);
+/// rt_session.run(config, reader).await.unwrap();
+/// # }
+/// ```
+///
+pub struct PacedReader<R> {
+    reader: R,
+    bytes_per_second: f64,
+    bytes_read: u64,
+    started: Option<Instant>,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl<R> PacedReader<R> {
+    /// Creates a reader which releases bytes_per_second bytes of the inner reader every second.
+    ///
+    /// # Errors
+    ///
+    /// This function will return Error::InvalidInput if bytes_per_second is not a positive number.
+    pub fn new(reader: R, bytes_per_second: f64) -> Result<Self> {
+        if !(bytes_per_second.is_finite() && bytes_per_second > 0.0) {
+            return Err(Error::InvalidInput(format!(
+                "invalid pacing rate of {} bytes per second",
+                bytes_per_second
+            )));
+        }
+        Ok(Self {
+            reader,
+            bytes_per_second,
+            bytes_read: 0,
+            started: None,
+            sleep: None,
+        })
+    }
+
+    /// Creates a reader paced to match raw audio in the given format, which is assumed to have a single channel.
+    /// If speed is set, the pace is multiplied by it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return Error::InvalidInput if the audio format is not raw, or is missing its encoding or sample rate,
+    /// as the duration of the audio can't be worked out from its length otherwise.
+    pub fn from_audio_format(
+        reader: R,
+        audio_format: &AudioFormat,
+        speed: Option<f64>,
+    ) -> Result<Self> {
+        let Some(bytes_per_second) = bytes_per_second(audio_format) else {
+            return Err(Error::InvalidInput(
+                "pacing requires a raw audio format with an encoding and sample rate".to_owned(),
+            ));
+        };
+        Self::new(reader, bytes_per_second * speed.unwrap_or(1.0))
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for PacedReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        let started = *this.started.get_or_insert_with(Instant::now);
+        let due = started + Duration::from_secs_f64(this.bytes_read as f64 / this.bytes_per_second);
+        if due > Instant::now() {
+            let sleep = this.sleep.get_or_insert_with(|| Box::pin(sleep_until(due)));
+            sleep.as_mut().reset(due);
+            if sleep.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+
+        let filled = buf.filled().len();
+        let res = Pin::new(&mut this.reader).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = res {
+            this.bytes_read += (buf.filled().len() - filled) as u64;
+        }
+        res
+    }
+}
+
+/// Returns the number of bytes in a second of single channel audio in the given format,
+/// or None if the format is not raw or is missing its encoding or sample rate.
+pub(crate) fn bytes_per_second(audio_format: &AudioFormat) -> Option<f64> {
+    let sample_rate = audio_format.sample_rate?;
+    Some(bytes_per_sample(audio_format)? as f64 * sample_rate as f64)
+}
+
+/// Returns the number of bytes in a sample of audio in the given format,
+/// or None if the format is not raw or is missing its encoding.
+pub(crate) fn bytes_per_sample(audio_format: &AudioFormat) -> Option<u64> {
+    let (audio_format::Type::Raw, Some(encoding)) =
+        (audio_format.type_value, audio_format.encoding)
+    else {
+        return None;
+    };
+    Some(match encoding {
+        audio_format::Encoding::PcmF32le => 4,
+        audio_format::Encoding::PcmS16le => 2,
+        audio_format::Encoding::Mulaw => 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn test_paced_reader() {
+        let mut audio_format = AudioFormat::new(audio_format::Type::Raw);
+        audio_format.encoding = Some(audio_format::Encoding::PcmS16le);
+        audio_format.sample_rate = Some(16000);
+
+        // 8000 bytes is 250ms of audio, or 62.5ms at 4x speed
+        let audio = std::io::Cursor::new(vec![0u8; 8000]);
+        let mut reader = PacedReader::from_audio_format(audio, &audio_format, Some(4.0)).unwrap();
+        let started = std::time::Instant::now();
+        let mut buffer = vec![0u8; 2000];
+        let mut chunks = 0;
+        while reader.read(&mut buffer).await.unwrap() > 0 {
+            chunks += 1;
+        }
+        let elapsed = started.elapsed();
+        assert_eq!(chunks, 4);
+        // the last chunk is released once the first three have played
+        assert!(elapsed >= Duration::from_millis(46), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(500), "{:?}", elapsed);
+
+        audio_format.type_value = audio_format::Type::File;
+        assert!(PacedReader::from_audio_format(tokio::io::empty(), &audio_format, None).is_err());
+        assert!(PacedReader::new(tokio::io::empty(), 0.0).is_err());
+    }
+}
@@ -0,0 +1,274 @@
+//! Assembles the AddTranscript and AddPartialTranscript messages of a realtime session into a running transcript.
+
+use super::models::{
+    recognition_result::{AttachesTo, Type},
+    AddPartialTranscript, AddTranscript, RecognitionResult,
+};
+use super::ReadMessage;
+use crate::punctuation::Attachment;
+use std::collections::BTreeMap;
+
+/// A word or punctuation mark in the transcript, with the times (in seconds) that it starts and ends in the audio.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TranscriptWord {
+    /// The content of the most likely alternative.
+    pub content: String,
+    /// The time the word starts at in seconds.
+    pub start_time: f32,
+    /// The time the word ends at in seconds.
+    pub end_time: f32,
+    /// The confidence of the most likely alternative, between 0 and 1.
+    pub confidence: f32,
+    /// The speaker label, if speaker diarization is enabled.
+    pub speaker: Option<String>,
+    /// The channel label, if channel diarization is enabled.
+    pub channel: Option<String>,
+    /// Whether this is a punctuation mark rather than a word.
+    pub is_punctuation: bool,
+    /// Whether this punctuation mark ends a sentence.
+    pub is_eos: bool,
+    /// How the item joins onto its neighbours. If the server didn't send attaches_to, words attach to neither
+    /// neighbour and punctuation attaches according to the mark, e.g. an opening bracket attaches to the next item.
+    pub attaches_to: AttachesTo,
+    /// Whether the word came from an AddTranscript, and so will not change. Words from partials may still be revised.
+    pub is_final: bool,
+}
+
+impl TranscriptWord {
+    fn from_result(result: &RecognitionResult, is_final: bool) -> Option<Self> {
+        let alternative = result.alternatives.as_ref()?.first()?;
+        let is_punctuation = match result.type_value {
+            Type::Word => false,
+            Type::Punctuation => true,
+            Type::SpeakerChange => return None,
+        };
+        let attaches_to = result.attaches_to.unwrap_or_else(|| {
+            Attachment::from_content(is_punctuation, &alternative.content).into()
+        });
+        Some(Self {
+            content: alternative.content.clone(),
+            start_time: result.start_time,
+            end_time: result.end_time,
+            confidence: alternative.confidence,
+            speaker: alternative.speaker.clone(),
+            channel: result.channel.clone(),
+            is_punctuation,
+            is_eos: result.is_eos == Some(true),
+            attaches_to,
+            is_final,
+        })
+    }
+}
+
+/// TranscriptBuilder keeps track of the finalised words of a session, and the partial words after them.
+///
+/// Each AddPartialTranscript replaces the partial words of its channel, and each AddTranscript finalises its words,
+/// dropping any partial words that end before it does. The transcript can be read at any point in the session.
+///
+/// # Example
+///
+/// ```
+/// use speechmatics::realtime::{transcript::TranscriptBuilder, ReadMessage};
+///
+/// let mut builder = TranscriptBuilder::new();
+/// let message: ReadMessage = serde_json::from_str(r#"{
+///     "message": "AddTranscript",
+///     "metadata": {"start_time": 0.0, "end_time": 1.0, "transcript": "Hello."},
+///     "results": [
+///         {"type": "word", "start_time": 0.0, "end_time": 0.9, "alternatives": [{"content": "Hello", "confidence": 1.0}]},
+///         {"type": "punctuation", "start_time": 0.9, "end_time": 0.9, "attaches_to": "previous", "is_eos": true,
+///          "alternatives": [{"content": ".", "confidence": 1.0}]}
+///     ]
+/// }"#).unwrap();
+/// builder.handle_message(&message);
+/// assert_eq!(builder.text(), "Hello.");
+/// ```
+///
+#[derive(Clone, Debug, Default)]
+pub struct TranscriptBuilder {
+    finals: Vec<TranscriptWord>,
+    partials: BTreeMap<Option<String>, Vec<TranscriptWord>>,
+}
+
+impl TranscriptBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the transcript from a message. Returns true if the message was a transcript, and false if it was ignored.
+    pub fn handle_message(&mut self, message: &ReadMessage) -> bool {
+        match message {
+            ReadMessage::AddTranscript(transcript) => self.add_transcript(transcript),
+            ReadMessage::AddPartialTranscript(partial) => self.add_partial_transcript(partial),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Appends the words of an AddTranscript to the finalised words.
+    pub fn add_transcript(&mut self, transcript: &AddTranscript) {
+        let end_time = transcript.metadata.end_time;
+        let channel = channel_of(&transcript.results);
+        if let Some(partials) = self.partials.get_mut(&channel) {
+            partials.retain(|word| word.end_time > end_time);
+        }
+        self.finals.extend(
+            transcript
+                .results
+                .iter()
+                .filter_map(|result| TranscriptWord::from_result(result, true)),
+        );
+    }
+
+    /// Replaces the partial words of the channel with those of an AddPartialTranscript.
+    pub fn add_partial_transcript(&mut self, partial: &AddPartialTranscript) {
+        let words = partial
+            .results
+            .iter()
+            .filter_map(|result| TranscriptWord::from_result(result, false))
+            .collect();
+        self.partials.insert(channel_of(&partial.results), words);
+    }
+
+    /// Returns the finalised words, followed by the current partial words, ordered by start time.
+    pub fn words(&self) -> Vec<TranscriptWord> {
+        let mut words: Vec<TranscriptWord> = self
+            .finals
+            .iter()
+            .chain(self.partials.values().flatten())
+            .cloned()
+            .collect();
+        words.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+        words
+    }
+
+    /// Returns only the finalised words, in the order they were received.
+    pub fn final_words(&self) -> &[TranscriptWord] {
+        &self.finals
+    }
+
+    /// Returns the text of the transcript, including the current partial words.
+    pub fn text(&self) -> String {
+        join_words(&self.words())
+    }
+
+    /// Returns the text of the finalised words only.
+    pub fn final_text(&self) -> String {
+        join_words(&self.finals)
+    }
+
+    /// Returns the text spoken by each speaker, including the current partial words.
+    /// Words are grouped by their speaker label, or by their channel for channel diarization.
+    pub fn text_by_speaker(&self) -> BTreeMap<Option<String>, String> {
+        let mut speakers: BTreeMap<Option<String>, Vec<TranscriptWord>> = BTreeMap::new();
+        for word in self.words() {
+            let speaker = word.speaker.clone().or_else(|| word.channel.clone());
+            speakers.entry(speaker).or_default().push(word);
+        }
+        speakers
+            .into_iter()
+            .map(|(speaker, words)| (speaker, join_words(&words)))
+            .collect()
+    }
+
+    /// Clears all the words, e.g. to start a new session with the same builder.
+    pub fn clear(&mut self) {
+        self.finals.clear();
+        self.partials.clear();
+    }
+}
+
+/// Joins words into text, with a space between items unless one of them attaches to the other.
+pub fn join_words(words: &[TranscriptWord]) -> String {
+    let mut text = String::new();
+    let mut attach_next = true;
+    for word in words {
+        let attach = Attachment::from(word.attaches_to);
+        if !attach_next && !attach.joins_previous() {
+            text.push(' ');
+        }
+        text.push_str(&word.content);
+        attach_next = attach.joins_next();
+    }
+    text
+}
+
+fn channel_of(results: &[RecognitionResult]) -> Option<String> {
+    results.first().and_then(|result| result.channel.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(kind: &str, end_time: f32, results: serde_json::Value) -> ReadMessage {
+        serde_json::from_value(serde_json::json!({
+            "message": kind,
+            "metadata": {"start_time": 0.0, "end_time": end_time, "transcript": ""},
+            "results": results
+        }))
+        .unwrap()
+    }
+
+    fn result(content: &str, start_time: f32, speaker: &str) -> serde_json::Value {
+        let punctuation = !content.chars().any(char::is_alphanumeric);
+        let mut result = serde_json::json!({
+            "type": if punctuation { "punctuation" } else { "word" },
+            "start_time": start_time,
+            "end_time": start_time + 0.5,
+            "alternatives": [{"content": content, "confidence": 0.9, "speaker": speaker}]
+        });
+        if content == "(" {
+            result["attaches_to"] = "next".into();
+        }
+        result
+    }
+
+    #[test]
+    fn test_transcript_builder() {
+        let mut builder = TranscriptBuilder::new();
+        let partial = message(
+            "AddPartialTranscript",
+            1.5,
+            serde_json::json!([result("Hello", 0.0, "S1"), result("world", 1.0, "S1")]),
+        );
+        assert!(builder.handle_message(&partial));
+        assert_eq!(builder.text(), "Hello world");
+        assert_eq!(builder.final_text(), "");
+
+        let fin = message(
+            "AddTranscript",
+            1.0,
+            serde_json::json!([result("Hello", 0.0, "S1"), result(",", 0.5, "S1")]),
+        );
+        builder.handle_message(&fin);
+        assert_eq!(builder.text(), "Hello, world");
+        assert_eq!(builder.final_text(), "Hello,");
+
+        let partial = message(
+            "AddPartialTranscript",
+            3.0,
+            serde_json::json!([
+                result("(", 1.0, "S2"),
+                result("hi", 1.0, "S2"),
+                result(")", 1.5, "S2"),
+                result("there", 2.0, "S1")
+            ]),
+        );
+        builder.handle_message(&partial);
+        assert_eq!(builder.text(), "Hello, (hi) there");
+        let words = builder.words();
+        assert!(words[1].is_final && !words[2].is_final);
+        assert_eq!(
+            builder.text_by_speaker(),
+            BTreeMap::from([
+                (Some("S1".to_owned()), "Hello, there".to_owned()),
+                (Some("S2".to_owned()), "(hi)".to_owned()),
+            ])
+        );
+
+        builder.clear();
+        assert_eq!(builder.text(), "");
+    }
+}
@@ -0,0 +1,327 @@
+//! Groups the words of a transcript into speaker turns, for both batch and realtime results.
+//!
+//! A turn is a run of consecutive words from the same speaker (or channel, for channel diarization).
+//! Punctuation which attaches to the previous word never starts a new turn, so a turn always ends on its closing
+//! punctuation, and punctuation which attaches to the next word (e.g. an opening bracket) goes into the turn of that word.
+
+use crate::punctuation::Attachment;
+use std::collections::HashMap;
+
+/// A continuous stretch of speech from a single speaker.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpeakerTurn {
+    /// The speaker label, e.g. S1, or the channel label if only channel diarization was used.
+    /// This is None if the results have no speaker or channel.
+    pub speaker: Option<String>,
+    /// The channel label, if channel diarization was used.
+    pub channel: Option<String>,
+    /// The time the first word of the turn starts at in seconds.
+    pub start_time: f32,
+    /// The time the last word of the turn ends at in seconds.
+    pub end_time: f32,
+    /// The text of the turn, with punctuation joined onto the words around it.
+    pub text: String,
+    /// The words and punctuation marks in the turn.
+    pub words: Vec<TurnWord>,
+}
+
+/// A word or punctuation mark in a speaker turn.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TurnWord {
+    /// The content of the most likely alternative.
+    pub content: String,
+    /// The time the word starts at in seconds.
+    pub start_time: f32,
+    /// The time the word ends at in seconds.
+    pub end_time: f32,
+    /// The confidence of the most likely alternative, between 0 and 1.
+    pub confidence: f32,
+    /// Whether this is a punctuation mark rather than a word.
+    pub is_punctuation: bool,
+}
+
+/// A result normalised from either the batch or realtime models.
+struct Item {
+    word: TurnWord,
+    speaker: Option<String>,
+    channel: Option<String>,
+    joins_previous: bool,
+    joins_next: bool,
+    speaker_change: bool,
+}
+
+/// Groups batch results into speaker turns.
+///
+/// channel_labels should be the channel_diarization_labels of the job, if any. Results on the default channel names
+/// (channel_1, channel_2 and so on) are relabelled with them, and results which already carry a label are left as is.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn run() {
+/// use speechmatics::{batch::BatchClient, turns::batch_speaker_turns};
+///
+/// let batch_client = BatchClient::new("API_KEY", None).unwrap();
+/// let transcript = batch_client.get_json_result("JOB_ID").await.unwrap();
+/// for turn in batch_speaker_turns(&transcript.results, None) {
+///     println!("{:?}: {}", turn.speaker, turn.text);
+/// }
+/// # }
+/// ```
+///
+#[cfg(feature = "batch")]
+pub fn batch_speaker_turns(
+    results: &[crate::batch::models::RecognitionResult],
+    channel_labels: Option<&[String]>,
+) -> Vec<SpeakerTurn> {
+    use crate::batch::models::recognition_result::Type;
+
+    let items = results.iter().filter_map(|result| {
+        let alternative = result.alternatives.as_ref()?.first()?;
+        let is_punctuation = result.type_value == Type::Punctuation;
+        let attach = Attachment::from_content(is_punctuation, &alternative.content);
+        Some(Item {
+            word: TurnWord {
+                content: alternative.content.clone(),
+                start_time: result.start_time,
+                end_time: result.end_time,
+                confidence: alternative.confidence,
+                is_punctuation,
+            },
+            speaker: alternative.speaker.clone(),
+            channel: result.channel.clone(),
+            joins_previous: attach.joins_previous(),
+            joins_next: attach.joins_next(),
+            speaker_change: false,
+        })
+    });
+    group_turns(items, channel_labels)
+}
+
+/// Groups realtime results, e.g. those collected from AddTranscript messages, into speaker turns.
+///
+/// SpeakerChange results always start a new turn. channel_labels relabels the default channel names
+/// (channel_1, channel_2 and so on), in the same way as for batch_speaker_turns.
+#[cfg(feature = "realtime")]
+pub fn realtime_speaker_turns(
+    results: &[crate::realtime::models::RecognitionResult],
+    channel_labels: Option<&[String]>,
+) -> Vec<SpeakerTurn> {
+    use crate::realtime::models::recognition_result::Type;
+
+    let items = results.iter().filter_map(|result| {
+        if result.type_value == Type::SpeakerChange {
+            return Some(Item {
+                word: TurnWord::default(),
+                speaker: None,
+                channel: result.channel.clone(),
+                joins_previous: false,
+                joins_next: false,
+                speaker_change: true,
+            });
+        }
+        let alternative = result.alternatives.as_ref()?.first()?;
+        let is_punctuation = result.type_value == Type::Punctuation;
+        let attach = result.attaches_to.map_or_else(
+            || Attachment::from_content(is_punctuation, &alternative.content),
+            Attachment::from,
+        );
+        Some(Item {
+            word: TurnWord {
+                content: alternative.content.clone(),
+                start_time: result.start_time,
+                end_time: result.end_time,
+                confidence: alternative.confidence,
+                is_punctuation,
+            },
+            speaker: alternative.speaker.clone(),
+            channel: result.channel.clone(),
+            joins_previous: attach.joins_previous(),
+            joins_next: attach.joins_next(),
+            speaker_change: false,
+        })
+    });
+    group_turns(items, channel_labels)
+}
+
+fn group_turns(
+    items: impl Iterator<Item = Item>,
+    channel_labels: Option<&[String]>,
+) -> Vec<SpeakerTurn> {
+    let labels: HashMap<String, &String> = channel_labels
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .map(|(i, label)| (format!("channel_{}", i + 1), label))
+        .collect();
+    let items = items.map(|mut item| {
+        item.channel = item.channel.map(|channel| {
+            labels
+                .get(&channel)
+                .map_or(channel, |label| (*label).clone())
+        });
+        item.speaker = item.speaker.or_else(|| item.channel.clone());
+        item
+    });
+
+    let mut turns: Vec<SpeakerTurn> = vec![];
+    let mut new_turn = true;
+    let mut attach_next = false;
+    // punctuation which attaches to the next word is held back until that word, so that it goes into the same turn
+    let mut pending: Vec<Item> = vec![];
+    for item in items {
+        if item.speaker_change {
+            new_turn = true;
+            continue;
+        }
+        if item.word.is_punctuation && item.joins_next {
+            pending.push(item);
+            continue;
+        }
+
+        let continues_turn = !new_turn
+            && turns.last().is_some_and(|turn| {
+                (item.word.is_punctuation && item.joins_previous)
+                    || (turn.speaker == item.speaker && turn.channel == item.channel)
+            });
+        if !continues_turn {
+            turns.push(SpeakerTurn {
+                speaker: item.speaker.clone(),
+                channel: item.channel.clone(),
+                start_time: pending.first().unwrap_or(&item).word.start_time,
+                ..Default::default()
+            });
+            attach_next = true;
+        }
+        new_turn = false;
+        for item in pending.drain(..).chain([item]) {
+            push_word(&mut turns, item, &mut attach_next);
+        }
+    }
+
+    // punctuation at the very end has no word after it, so it ends the last turn instead
+    if let Some(first) = pending.first() {
+        if turns.is_empty() || new_turn {
+            turns.push(SpeakerTurn {
+                speaker: first.speaker.clone(),
+                channel: first.channel.clone(),
+                start_time: first.word.start_time,
+                ..Default::default()
+            });
+            attach_next = true;
+        }
+        for item in pending {
+            push_word(&mut turns, item, &mut attach_next);
+        }
+    }
+    turns
+}
+
+/// Adds a word or punctuation mark to the last turn, joining it onto the text around it.
+fn push_word(turns: &mut [SpeakerTurn], item: Item, attach_next: &mut bool) {
+    let Some(turn) = turns.last_mut() else {
+        return;
+    };
+    if !*attach_next && !item.joins_previous {
+        turn.text.push(' ');
+    }
+    turn.text.push_str(&item.word.content);
+    turn.end_time = turn.end_time.max(item.word.end_time);
+    turn.words.push(item.word);
+    *attach_next = item.joins_next;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_batch_speaker_turns() {
+        use crate::batch::models::{
+            recognition_result::Type, RecognitionAlternative, RecognitionResult,
+        };
+
+        let result =
+            |content: &str, start_time: f32, speaker: Option<&str>, channel: Option<&str>| {
+                let punctuation = !content.chars().any(char::is_alphanumeric);
+                let mut alternative =
+                    RecognitionAlternative::new(content.to_owned(), 0.9, "en".to_owned());
+                alternative.speaker = speaker.map(str::to_owned);
+                let mut result = RecognitionResult::new(
+                    start_time,
+                    start_time + 0.5,
+                    if punctuation {
+                        Type::Punctuation
+                    } else {
+                        Type::Word
+                    },
+                );
+                result.channel = channel.map(str::to_owned);
+                result.alternatives = Some(vec![alternative]);
+                result
+            };
+
+        let results = vec![
+            result("Hello", 0.0, Some("S1"), None),
+            result(",", 0.5, Some("S2"), None),
+            result("there", 0.5, Some("S1"), None),
+            result(".", 1.0, None, None),
+            result("Hi", 1.5, Some("S2"), None),
+            result("!", 2.0, Some("S2"), None),
+        ];
+        let turns = batch_speaker_turns(&results, None);
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].speaker.as_deref(), Some("S1"));
+        assert_eq!(turns[0].text, "Hello, there.");
+        assert_eq!((turns[0].start_time, turns[0].end_time), (0.0, 1.5));
+        assert_eq!(turns[0].words.len(), 4);
+        assert_eq!(turns[1].text, "Hi!");
+
+        // an opening bracket goes with the word after it, even if the speaker changes
+        let results = vec![
+            result("Hello", 0.0, Some("S1"), None),
+            result(".", 0.5, Some("S1"), None),
+            result("(", 1.0, Some("S2"), None),
+            result("laughs", 1.0, Some("S2"), None),
+            result(")", 1.5, Some("S2"), None),
+        ];
+        let turns = batch_speaker_turns(&results, None);
+        let text: Vec<_> = turns.iter().map(|turn| turn.text.as_str()).collect();
+        assert_eq!(text, vec!["Hello.", "(laughs)"]);
+        assert_eq!(turns[1].start_time, 1.0);
+
+        let results = vec![
+            result("Hello", 0.0, None, Some("channel_1")),
+            result("hi", 0.5, None, Some("channel_2")),
+            result("bye", 1.0, None, Some("agent")),
+        ];
+        let labels = vec!["agent".to_owned(), "customer".to_owned()];
+        let turns = batch_speaker_turns(&results, Some(&labels));
+        let speakers: Vec<_> = turns.iter().map(|turn| turn.speaker.as_deref()).collect();
+        assert_eq!(
+            speakers,
+            vec![Some("agent"), Some("customer"), Some("agent")]
+        );
+        assert_eq!(turns[1].channel.as_deref(), Some("customer"));
+    }
+
+    #[cfg(feature = "realtime")]
+    #[test]
+    fn test_realtime_speaker_turns() {
+        use crate::realtime::models::RecognitionResult;
+
+        let results: Vec<RecognitionResult> = serde_json::from_value(serde_json::json!([
+            {"type": "word", "start_time": 0.0, "end_time": 0.5, "alternatives": [{"content": "Hello", "confidence": 1.0, "speaker": "S1"}]},
+            {"type": "punctuation", "start_time": 0.5, "end_time": 0.5, "attaches_to": "previous", "alternatives": [{"content": ".", "confidence": 1.0, "speaker": "S1"}]},
+            {"type": "speaker_change", "start_time": 0.5, "end_time": 0.5},
+            {"type": "punctuation", "start_time": 1.0, "end_time": 1.0, "attaches_to": "next", "alternatives": [{"content": "(", "confidence": 1.0, "speaker": "S1"}]},
+            {"type": "word", "start_time": 1.0, "end_time": 1.5, "alternatives": [{"content": "yes", "confidence": 1.0, "speaker": "S1"}]},
+            {"type": "punctuation", "start_time": 1.5, "end_time": 1.5, "alternatives": [{"content": ")", "confidence": 1.0, "speaker": "S1"}]}
+        ]))
+        .unwrap();
+        let turns = realtime_speaker_turns(&results, None);
+        let text: Vec<_> = turns.iter().map(|turn| turn.text.as_str()).collect();
+        assert_eq!(text, vec!["Hello.", "(yes)"]);
+    }
+}